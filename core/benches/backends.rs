@@ -0,0 +1,81 @@
+//! Move generation and flipping for every backend in `core::backend`, over
+//! positions from random games. Throughput is in moves: legal move lists
+//! generated, or moves flipped.
+
+use core::{backend, testing::random_games};
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use std::hint::black_box;
+
+type Backend = (&'static str, fn(u64, u64) -> u64, fn(u64, u64, u64) -> u64);
+
+const BACKENDS: [Backend; 4] = [
+    ("shift", backend::shift::legal_moves, backend::shift::flips),
+    (
+        "kogge_stone",
+        backend::kogge_stone::legal_moves,
+        backend::kogge_stone::flips,
+    ),
+    (
+        "lookup",
+        backend::lookup::legal_moves,
+        backend::lookup::flips,
+    ),
+    ("simd", backend::simd::legal_moves, backend::simd::flips),
+];
+
+/// `(player, opponent, legal moves)` for every position of some random
+/// games where the side to move has a move.
+fn positions(games: usize) -> Vec<(u64, u64, u64)> {
+    random_games(7)
+        .take(games)
+        .flat_map(|game| game.positions)
+        .filter(|board| board.legal_moves() != 0)
+        .map(|board| {
+            let (player, opponent) = board.get_current_player_and_opponent();
+            (player, opponent, board.legal_moves())
+        })
+        .collect()
+}
+
+fn bench_backends(c: &mut Criterion) {
+    let positions = positions(50);
+    let moves: u64 = positions
+        .iter()
+        .map(|&(_, _, legal)| legal.count_ones() as u64)
+        .sum();
+
+    let mut group = c.benchmark_group("legal_moves");
+    group.throughput(Throughput::Elements(positions.len() as u64));
+    for (name, legal_moves, _) in BACKENDS {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                positions.iter().fold(0, |acc, &(player, opponent, _)| {
+                    acc ^ legal_moves(black_box(player), black_box(opponent))
+                })
+            })
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("flips");
+    group.throughput(Throughput::Elements(moves));
+    for (name, _, flips) in BACKENDS {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let mut acc = 0;
+                for &(player, opponent, mut legal) in &positions {
+                    while legal != 0 {
+                        let pos = legal & legal.wrapping_neg();
+                        acc ^= flips(black_box(player), black_box(opponent), pos);
+                        legal &= legal - 1;
+                    }
+                }
+                acc
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_backends);
+criterion_main!(benches);
@@ -0,0 +1,105 @@
+//! Copy-make against make/unmake, over random playouts and a small tree
+//! walk.
+//!
+//! The board is a few machine words, so copying is already cheap; these
+//! numbers tell whether unmaking pays off for a given search, rather than
+//! assuming it does.
+
+use core::{Move, OthelloBoard, testing::random_games};
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+
+/// Plays each game forward and walks back to the start by keeping a copy
+/// of every position.
+fn copy_make(games: &[Vec<Move>]) -> u64 {
+    let mut total = 0;
+    let mut stack = Vec::with_capacity(64);
+    for game in games {
+        let mut board = OthelloBoard::new();
+        for mv in game {
+            stack.push(board);
+            match *mv {
+                Move::Place(square) => total += board.try_place(square).map_or(0, |o| o.flipped),
+                Move::Pass => total += u64::from(board.pass()),
+            }
+        }
+        while let Some(previous) = stack.pop() {
+            board = previous;
+        }
+        total ^= board.black;
+    }
+    total
+}
+
+/// The same walk with [`OthelloBoard::make_move`] and
+/// [`OthelloBoard::unmake_move`].
+fn make_unmake(games: &[Vec<Move>]) -> u64 {
+    let mut total = 0;
+    let mut undos = Vec::with_capacity(64);
+    for game in games {
+        let mut board = OthelloBoard::new();
+        for mv in game {
+            let undo = match *mv {
+                Move::Place(square) => board.make_move(square),
+                Move::Pass => Ok(board.make_null_move()),
+            };
+            if let Ok(undo) = undo {
+                total += undo.flipped();
+                undos.push(undo);
+            }
+        }
+        while let Some(undo) = undos.pop() {
+            board.unmake_move(undo);
+        }
+        total ^= board.black;
+    }
+    total
+}
+
+fn tree_copy(board: &OthelloBoard, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    board
+        .legal_squares()
+        .into_iter()
+        .map(|square| {
+            let mut next = *board;
+            let _ = next.try_place(square);
+            tree_copy(&next, depth - 1)
+        })
+        .sum()
+}
+
+fn tree_unmake(board: &mut OthelloBoard, depth: u32) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let mut nodes = 0;
+    for square in board.legal_squares() {
+        if let Ok(undo) = board.make_move(square) {
+            nodes += tree_unmake(board, depth - 1);
+            board.unmake_move(undo);
+        }
+    }
+    nodes
+}
+
+fn bench(c: &mut Criterion) {
+    let games: Vec<_> = random_games(4).take(100).map(|game| game.moves).collect();
+    let mut group = c.benchmark_group("playouts");
+    group.bench_function("copy_make", |b| b.iter(|| copy_make(black_box(&games))));
+    group.bench_function("make_unmake", |b| b.iter(|| make_unmake(black_box(&games))));
+    group.finish();
+
+    let board = OthelloBoard::new();
+    let mut group = c.benchmark_group("tree_depth_6");
+    group.bench_function("copy_make", |b| b.iter(|| tree_copy(black_box(&board), 6)));
+    group.bench_function("make_unmake", |b| {
+        b.iter(|| tree_unmake(&mut black_box(board), 6))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
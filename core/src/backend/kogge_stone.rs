@@ -0,0 +1,61 @@
+//! Kogge-Stone occluded fills: each direction takes three shift steps
+//! instead of six, at the cost of more masking.
+
+use super::shift::{FILE_A, FILE_H};
+
+/// `(shift, <<-mask, >>-mask)` for each line direction. The masks drop
+/// squares that a shift would wrap onto the other side of the board.
+const DIRECTIONS: [(u32, u64, u64); 4] = [
+    (1, !FILE_A, !FILE_H),
+    (8, u64::MAX, u64::MAX),
+    (7, !FILE_H, !FILE_A),
+    (9, !FILE_A, !FILE_H),
+];
+
+/// Fills `generator` through `propagator` towards the higher squares.
+#[inline]
+fn fill_up(mut generator: u64, propagator: u64, shift: u32, mask: u64) -> u64 {
+    let mut propagator = propagator & mask;
+    generator |= propagator & (generator << shift);
+    propagator &= propagator << shift;
+    generator |= propagator & (generator << (2 * shift));
+    propagator &= propagator << (2 * shift);
+    generator | (propagator & (generator << (4 * shift)))
+}
+
+/// Fills `generator` through `propagator` towards the lower squares.
+#[inline]
+fn fill_down(mut generator: u64, propagator: u64, shift: u32, mask: u64) -> u64 {
+    let mut propagator = propagator & mask;
+    generator |= propagator & (generator >> shift);
+    propagator &= propagator >> shift;
+    generator |= propagator & (generator >> (2 * shift));
+    propagator &= propagator >> (2 * shift);
+    generator | (propagator & (generator >> (4 * shift)))
+}
+
+pub fn legal_moves(player: u64, opponent: u64) -> u64 {
+    let mut legal = 0;
+    for (shift, up, down) in DIRECTIONS {
+        let run = fill_up(player, opponent, shift, up) & opponent;
+        legal |= (run << shift) & up;
+        let run = fill_down(player, opponent, shift, down) & opponent;
+        legal |= (run >> shift) & down;
+    }
+    legal & !(player | opponent)
+}
+
+pub fn flips(player: u64, opponent: u64, pos: u64) -> u64 {
+    let mut to_flip = 0;
+    for (shift, up, down) in DIRECTIONS {
+        let run = fill_up(pos, opponent, shift, up) & opponent;
+        if (run << shift) & up & player != 0 {
+            to_flip |= run;
+        }
+        let run = fill_down(pos, opponent, shift, down) & opponent;
+        if (run >> shift) & down & player != 0 {
+            to_flip |= run;
+        }
+    }
+    to_flip
+}
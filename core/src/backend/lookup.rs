@@ -0,0 +1,147 @@
+//! Precomputed line tables: each of the four lines through the played
+//! square is gathered into a byte and its flips are looked up.
+//!
+//! Tables only pay off for a single square, so `legal_moves` uses
+//! [`kogge_stone`](super::kogge_stone).
+
+use super::shift::FILE_A;
+
+pub use super::kogge_stone::legal_moves;
+
+/// Gathers file 0 of a board into a byte, rank `r` at bit `r`.
+const FILE_TO_BYTE: u64 = 0x0102_0408_1020_4080;
+
+/// `OUTFLANK[p][line]`: for an opponent `line`, the squares just past the
+/// opponent runs on either side of position `p`. A square only counts when
+/// at least one opponent disc lies between it and `p`.
+static OUTFLANK: [[u8; 256]; 8] = {
+    let mut table = [[0; 256]; 8];
+    let mut p = 0;
+    while p < 8 {
+        let mut line = 0;
+        while line < 256 {
+            let mut outflank = 0;
+            let mut i = p + 1;
+            while i < 8 && line & (1 << i) != 0 {
+                i += 1;
+            }
+            if i > p + 1 && i < 8 {
+                outflank |= 1 << i;
+            }
+            let mut i = p as i32 - 1;
+            while i >= 0 && line & (1 << i) != 0 {
+                i -= 1;
+            }
+            if i < p as i32 - 1 && i >= 0 {
+                outflank |= 1 << i;
+            }
+            table[p][line] = outflank;
+            line += 1;
+        }
+        p += 1;
+    }
+    table
+};
+
+/// `FLIPPED[p][outflank]`: the squares strictly between `p` and each bit of
+/// `outflank`.
+static FLIPPED: [[u8; 256]; 8] = {
+    let mut table = [[0; 256]; 8];
+    let mut p = 0;
+    while p < 8 {
+        let mut outflank = 0;
+        while outflank < 256 {
+            let mut flipped = 0;
+            let mut i = 0;
+            while i < 8 {
+                if outflank & (1 << i) != 0 {
+                    let (low, high) = if i < p { (i, p) } else { (p, i) };
+                    let mut j = low + 1;
+                    while j < high {
+                        flipped |= 1 << j;
+                        j += 1;
+                    }
+                }
+                i += 1;
+            }
+            table[p][outflank] = flipped;
+            outflank += 1;
+        }
+        p += 1;
+    }
+    table
+};
+
+/// `FILE[byte]`: the inverse of gathering with [`FILE_TO_BYTE`].
+static FILE: [u64; 256] = {
+    let mut table = [0; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        let mut rank = 0;
+        while rank < 8 {
+            if byte & (1 << rank) != 0 {
+                table[byte] |= 1 << (8 * rank);
+            }
+            rank += 1;
+        }
+        byte += 1;
+    }
+    table
+};
+
+/// `(a1-h8 diagonal, a8-h1 diagonal)` through each square.
+static DIAGONALS: [(u64, u64); 64] = {
+    let mut table = [(0, 0); 64];
+    let mut index = 0;
+    while index < 64 {
+        let (x, y) = ((index % 8) as i32, (index / 8) as i32);
+        let mut square = 0;
+        while square < 64 {
+            let (i, j) = (square % 8, square / 8);
+            if i - j == x - y {
+                table[index].0 |= 1 << square;
+            }
+            if i + j == x + y {
+                table[index].1 |= 1 << square;
+            }
+            square += 1;
+        }
+        index += 1;
+    }
+    table
+};
+
+/// Flips along one line, with `player` and `opponent` gathered into bytes.
+#[inline]
+fn line_flips(player: u64, opponent: u64, p: usize) -> u64 {
+    let outflank = OUTFLANK[p][opponent as usize] & player as u8;
+    FLIPPED[p][outflank as usize] as u64
+}
+
+/// Gathers a diagonal (already masked) into a byte, file `x` at bit `x`.
+#[inline]
+const fn gather_diagonal(masked: u64) -> u64 {
+    masked.wrapping_mul(FILE_A) >> 56
+}
+
+pub fn flips(player: u64, opponent: u64, pos: u64) -> u64 {
+    let index = pos.trailing_zeros() as usize;
+    let (x, y) = (index % 8, index / 8);
+
+    let row = |board: u64| (board >> (8 * y)) & 0xff;
+    let mut to_flip = line_flips(row(player), row(opponent), x) << (8 * y);
+
+    let column = |board: u64| ((board >> x) & FILE_A).wrapping_mul(FILE_TO_BYTE) >> 56;
+    to_flip |= FILE[line_flips(column(player), column(opponent), y) as usize] << x;
+
+    let (diagonal, anti_diagonal) = DIAGONALS[index];
+    for mask in [diagonal, anti_diagonal] {
+        let flipped = line_flips(
+            gather_diagonal(player & mask),
+            gather_diagonal(opponent & mask),
+            x,
+        );
+        to_flip |= flipped.wrapping_mul(FILE_A) & mask;
+    }
+    to_flip
+}
@@ -0,0 +1,110 @@
+//! Interchangeable implementations of move generation and flipping.
+//!
+//! Every backend exposes the same two functions:
+//!
+//! - `legal_moves(player, opponent)`: empty squares where `player` may move.
+//! - `flips(player, opponent, pos)`: discs flipped when `player` plays the
+//!   single-bit `pos`, which must be empty.
+//!
+//! All of them are always compiled so they can be tested and benchmarked
+//! against each other. [`OthelloBoard`](crate::OthelloBoard) uses [`active`],
+//! picked by cargo feature: `simd`, then `lookup`, then `kogge-stone`, and
+//! [`shift`] when none is enabled.
+
+pub mod kogge_stone;
+pub mod lookup;
+pub mod shift;
+pub mod simd;
+
+#[cfg(feature = "simd")]
+pub use self::simd as active;
+
+#[cfg(all(feature = "lookup", not(feature = "simd")))]
+pub use self::lookup as active;
+
+#[cfg(all(
+    feature = "kogge-stone",
+    not(any(feature = "lookup", feature = "simd"))
+))]
+pub use self::kogge_stone as active;
+
+#[cfg(not(any(feature = "kogge-stone", feature = "lookup", feature = "simd")))]
+pub use self::shift as active;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{Rng, random_games};
+    use shift::{FILE_A, FILE_H};
+
+    type Backend = (&'static str, fn(u64, u64) -> u64, fn(u64, u64, u64) -> u64);
+
+    const BACKENDS: [Backend; 4] = [
+        ("shift", shift::legal_moves, shift::flips),
+        ("kogge_stone", kogge_stone::legal_moves, kogge_stone::flips),
+        ("lookup", lookup::legal_moves, lookup::flips),
+        ("simd", simd::legal_moves, simd::flips),
+    ];
+
+    /// Checks every backend against [`shift`], the original implementation.
+    fn check(player: u64, opponent: u64) {
+        let legal = shift::legal_moves(player, opponent);
+        let empty = !(player | opponent);
+        for (name, legal_moves, flips) in BACKENDS {
+            assert_eq!(
+                legal_moves(player, opponent),
+                legal,
+                "{name} legal_moves {player:#x} {opponent:#x}"
+            );
+            for index in 0..64 {
+                let pos = 1 << index;
+                if pos & empty == 0 {
+                    continue;
+                }
+                let expected = shift::flips(player, opponent, pos);
+                assert_eq!(
+                    flips(player, opponent, pos),
+                    expected,
+                    "{name} flips {player:#x} {opponent:#x} at {index}"
+                );
+                assert_eq!(expected != 0, pos & legal != 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_positions() {
+        let mut rng = Rng::new(5);
+        for _ in 0..3000 {
+            // Mixing draws varies the density from sparse to nearly full.
+            let occupied = match rng.below(3) {
+                0 => rng.next_u64() & rng.next_u64(),
+                1 => rng.next_u64(),
+                _ => rng.next_u64() | rng.next_u64(),
+            };
+            let player = occupied & rng.next_u64();
+            check(player, occupied & !player);
+        }
+    }
+
+    #[test]
+    fn test_edges_and_extremes() {
+        check(0, 0);
+        check(u64::MAX, 0);
+        check(0, u64::MAX);
+        check(FILE_A, FILE_H);
+        check(FILE_H, !FILE_H & !FILE_A);
+        check(1, !1 & !(1 << 63));
+        check(1 << 63, !1 & !(1 << 63));
+    }
+
+    #[test]
+    fn test_random_games() {
+        for game in random_games(6).take(200) {
+            for board in &game.positions {
+                let (player, opponent) = board.get_current_player_and_opponent();
+                check(player, opponent);
+            }
+        }
+    }
+}
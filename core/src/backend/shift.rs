@@ -0,0 +1,52 @@
+//! The original backend: the [`line!`](crate::line) macro run over the four
+//! line directions, each in both ways.
+
+use crate::line;
+
+pub(crate) const FILE_A: u64 = 0x0101_0101_0101_0101;
+pub(crate) const FILE_H: u64 = 0x8080_8080_8080_8080;
+
+pub(super) const LR_EDGE_MASK: u64 = 0x7e7e7e7e7e7e7e7e;
+pub(super) const TB_EDGE_MASK: u64 = 0x00FFFFFFFFFFFF00;
+pub(super) const LTRB_EDGE_MASK: u64 = 0x007e7e7e7e7e7e00;
+const SHIFT_MASK_LIST: [(u32, u64); 4] = [
+    (1, LR_EDGE_MASK),
+    (8, TB_EDGE_MASK),
+    (7, LTRB_EDGE_MASK),
+    (9, LTRB_EDGE_MASK),
+];
+
+pub fn legal_moves(player: u64, opponent: u64) -> u64 {
+    let empty_squares = !(player | opponent);
+
+    let mut legal = 0;
+    for (shift, mask) in SHIFT_MASK_LIST {
+        // Moves in the "positive" direction (e.g., right, down)
+        let mut line = line!(opponent & mask, player, >>, shift);
+        legal |= line >> shift;
+
+        // Moves in the "negative" direction (e.g., left, up)
+        line = line!(opponent & mask, player, <<, shift);
+        legal |= line << shift;
+    }
+
+    legal & empty_squares
+}
+
+pub fn flips(player: u64, opponent: u64, pos: u64) -> u64 {
+    let mut to_flip = 0;
+    for (shift, mask) in SHIFT_MASK_LIST {
+        // Flips in the "positive" direction
+        let line1 = line!(opponent & mask, pos, >>, shift);
+        if ((line1 >> shift) & player) != 0 {
+            to_flip |= line1;
+        }
+
+        // Flips in the "negative" direction
+        let line2 = line!(opponent & mask, pos, <<, shift);
+        if ((line2 << shift) & player) != 0 {
+            to_flip |= line2;
+        }
+    }
+    to_flip
+}
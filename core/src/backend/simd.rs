@@ -0,0 +1,51 @@
+//! The [`shift`](super::shift) algorithm written lane-wise over the four
+//! line directions, so the compiler can keep them in vector registers.
+//!
+//! There are no explicit SIMD instructions here, neither `std::simd` nor
+//! `core::arch`: the code is plain `[u64; 4]` lane arrays with no branches
+//! in the inner loops, left to the compiler's auto-vectorization. Whether it
+//! beats [`shift`](super::shift) depends on the target features enabled.
+
+use super::shift::{LR_EDGE_MASK, LTRB_EDGE_MASK, TB_EDGE_MASK};
+
+const SHIFTS: [u32; 4] = [1, 8, 7, 9];
+const MASKS: [u64; 4] = [LR_EDGE_MASK, TB_EDGE_MASK, LTRB_EDGE_MASK, LTRB_EDGE_MASK];
+
+type Lanes = [u64; 4];
+
+/// Runs of masked `opponent` discs next to `from`, in both ways of each
+/// direction.
+#[inline]
+fn runs(from: u64, opponent: u64) -> (Lanes, Lanes) {
+    let inner: Lanes = std::array::from_fn(|i| opponent & MASKS[i]);
+    let mut up: Lanes = std::array::from_fn(|i| inner[i] & (from << SHIFTS[i]));
+    let mut down: Lanes = std::array::from_fn(|i| inner[i] & (from >> SHIFTS[i]));
+    for _ in 0..5 {
+        for i in 0..4 {
+            up[i] |= inner[i] & (up[i] << SHIFTS[i]);
+            down[i] |= inner[i] & (down[i] >> SHIFTS[i]);
+        }
+    }
+    (up, down)
+}
+
+pub fn legal_moves(player: u64, opponent: u64) -> u64 {
+    let (up, down) = runs(player, opponent);
+    let mut legal = 0;
+    for i in 0..4 {
+        legal |= (up[i] << SHIFTS[i]) | (down[i] >> SHIFTS[i]);
+    }
+    legal & !(player | opponent)
+}
+
+pub fn flips(player: u64, opponent: u64, pos: u64) -> u64 {
+    let (up, down) = runs(pos, opponent);
+    let mut to_flip = 0;
+    for i in 0..4 {
+        // Branch-free: a run counts only when a player disc closes it.
+        let closed_up = ((up[i] << SHIFTS[i]) & player != 0) as u64;
+        let closed_down = ((down[i] >> SHIFTS[i]) & player != 0) as u64;
+        to_flip |= (up[i] & closed_up.wrapping_neg()) | (down[i] & closed_down.wrapping_neg());
+    }
+    to_flip
+}
@@ -0,0 +1,247 @@
+use std::{
+    fmt,
+    iter::FusedIterator,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Sub, SubAssign},
+};
+
+use crate::Square;
+
+/// A set of squares backed by a `u64` mask, using the same bit layout as
+/// [`Square`].
+#[rustfmt::skip]
+#[derive(PartialEq, Eq, Clone, Copy, Default, Hash)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+#[repr(transparent)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+    pub const EMPTY: Bitboard = Bitboard(0);
+    pub const FULL: Bitboard = Bitboard(u64::MAX);
+
+    #[inline]
+    pub const fn new(mask: u64) -> Self {
+        Bitboard(mask)
+    }
+
+    #[inline]
+    pub const fn bits(self) -> u64 {
+        self.0
+    }
+
+    #[inline]
+    pub const fn popcount(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    #[inline]
+    pub const fn contains(self, square: Square) -> bool {
+        self.0 & square.mask() != 0
+    }
+
+    #[inline]
+    pub const fn with(self, square: Square) -> Self {
+        Bitboard(self.0 | square.mask())
+    }
+
+    #[inline]
+    pub const fn without(self, square: Square) -> Self {
+        Bitboard(self.0 & !square.mask())
+    }
+
+    #[inline]
+    pub fn insert(&mut self, square: Square) {
+        self.0 |= square.mask();
+    }
+
+    #[inline]
+    pub fn remove(&mut self, square: Square) {
+        self.0 &= !square.mask();
+    }
+
+    /// Lowest set square, if any.
+    #[inline]
+    pub const fn first(self) -> Option<Square> {
+        if self.0 == 0 {
+            None
+        } else {
+            Square::from_index(self.0.trailing_zeros() as u8)
+        }
+    }
+
+    #[inline]
+    pub const fn iter(self) -> BitboardIter {
+        BitboardIter(self.0)
+    }
+}
+
+impl fmt::Debug for Bitboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Bitboard({:#018x})", self.0)
+    }
+}
+
+impl From<u64> for Bitboard {
+    #[inline]
+    fn from(value: u64) -> Self {
+        Bitboard(value)
+    }
+}
+
+impl From<Bitboard> for u64 {
+    #[inline]
+    fn from(value: Bitboard) -> Self {
+        value.0
+    }
+}
+
+impl From<Square> for Bitboard {
+    #[inline]
+    fn from(value: Square) -> Self {
+        Bitboard(value.mask())
+    }
+}
+
+impl PartialEq<u64> for Bitboard {
+    #[inline]
+    fn eq(&self, other: &u64) -> bool {
+        self.0 == *other
+    }
+}
+
+macro_rules! impl_bit_op {
+    ($trait:ident, $fn:ident, $assign_trait:ident, $assign_fn:ident, $op:tt) => {
+        impl<T: Into<Bitboard>> $trait<T> for Bitboard {
+            type Output = Bitboard;
+
+            #[inline]
+            fn $fn(self, rhs: T) -> Bitboard {
+                Bitboard(self.0 $op rhs.into().0)
+            }
+        }
+
+        impl<T: Into<Bitboard>> $assign_trait<T> for Bitboard {
+            #[inline]
+            fn $assign_fn(&mut self, rhs: T) {
+                self.0 = self.0 $op rhs.into().0;
+            }
+        }
+    };
+}
+
+impl_bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign, &);
+impl_bit_op!(BitOr, bitor, BitOrAssign, bitor_assign, |);
+impl_bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+impl<T: Into<Bitboard>> Sub<T> for Bitboard {
+    type Output = Bitboard;
+
+    /// Set difference.
+    #[inline]
+    fn sub(self, rhs: T) -> Bitboard {
+        Bitboard(self.0 & !rhs.into().0)
+    }
+}
+
+impl<T: Into<Bitboard>> SubAssign<T> for Bitboard {
+    #[inline]
+    fn sub_assign(&mut self, rhs: T) {
+        self.0 &= !rhs.into().0;
+    }
+}
+
+impl Not for Bitboard {
+    type Output = Bitboard;
+
+    #[inline]
+    fn not(self) -> Bitboard {
+        Bitboard(!self.0)
+    }
+}
+
+impl IntoIterator for Bitboard {
+    type Item = Square;
+    type IntoIter = BitboardIter;
+
+    #[inline]
+    fn into_iter(self) -> BitboardIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<Square> for Bitboard {
+    fn from_iter<I: IntoIterator<Item = Square>>(iter: I) -> Self {
+        iter.into_iter().fold(Bitboard::EMPTY, Bitboard::with)
+    }
+}
+
+/// Iterates over the set squares of a [`Bitboard`] from a1 to h8.
+#[derive(Debug, Clone)]
+pub struct BitboardIter(u64);
+
+impl Iterator for BitboardIter {
+    type Item = Square;
+
+    #[inline]
+    fn next(&mut self) -> Option<Square> {
+        if self.0 == 0 {
+            return None;
+        }
+        let index = self.0.trailing_zeros() as u8;
+        self.0 &= self.0 - 1;
+        Square::from_index(index)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for BitboardIter {}
+
+impl FusedIterator for BitboardIter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_operations() {
+        let a = Bitboard::from(Square::A1) | Square::B1 | Square::C1;
+        let b = Bitboard::from(Square::B1) | Square::H8;
+
+        assert_eq!(a.popcount(), 3);
+        assert_eq!(a & b, Bitboard::from(Square::B1));
+        assert_eq!(a | b, 0b111 | (1 << 63));
+        assert_eq!(a ^ b, 0b101 | (1 << 63));
+        assert_eq!(a - b, 0b101);
+        assert_eq!((!a).popcount(), 61);
+        assert!(a.contains(Square::C1));
+        assert!(!a.contains(Square::H8));
+
+        let mut c = a;
+        c.remove(Square::A1);
+        c.insert(Square::H8);
+        assert_eq!(c, 0b110 | (1 << 63));
+        assert!(Bitboard::EMPTY.is_empty());
+        assert_eq!(Bitboard::FULL.popcount(), 64);
+    }
+
+    #[test]
+    fn test_iter() {
+        let board = Bitboard::new((1 << 19) | (1 << 26) | (1 << 37) | (1 << 44));
+        let squares: Vec<Square> = board.iter().collect();
+        assert_eq!(squares, [Square::D3, Square::C4, Square::F5, Square::E6]);
+        assert_eq!(board.iter().len(), 4);
+        assert_eq!(board.first(), Some(Square::D3));
+        assert_eq!(Bitboard::EMPTY.first(), None);
+        assert_eq!(squares.into_iter().collect::<Bitboard>(), board);
+    }
+}
@@ -0,0 +1,174 @@
+use std::fmt;
+
+use crate::{OthelloBoard, OthelloColor};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DecodeBoardError {
+    /// A square is claimed by both colors.
+    Overlap,
+    InvalidTurn(u8),
+}
+
+impl fmt::Display for DecodeBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeBoardError::Overlap => f.write_str("black and white discs overlap"),
+            DecodeBoardError::InvalidTurn(b) => write!(f, "invalid side to move byte {b}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeBoardError {}
+
+/// Wire representation used by the serde implementations of
+/// [`OthelloBoard`], validated on the way in.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct BoardRepr {
+    black: u64,
+    white: u64,
+    turn: OthelloColor,
+    ply: u16,
+}
+
+impl From<OthelloBoard> for BoardRepr {
+    fn from(board: OthelloBoard) -> Self {
+        BoardRepr {
+            black: board.black,
+            white: board.white,
+            turn: board.turn,
+            ply: board.ply,
+        }
+    }
+}
+
+impl TryFrom<BoardRepr> for OthelloBoard {
+    type Error = DecodeBoardError;
+
+    fn try_from(repr: BoardRepr) -> Result<Self, Self::Error> {
+        if repr.black & repr.white != 0 {
+            return Err(DecodeBoardError::Overlap);
+        }
+        Ok(OthelloBoard {
+            black: repr.black,
+            white: repr.white,
+            turn: repr.turn,
+            ply: repr.ply,
+        })
+    }
+}
+
+impl serde::Serialize for OthelloBoard {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BoardRepr::from(*self).serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for OthelloBoard {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let repr = BoardRepr::deserialize(deserializer)?;
+        OthelloBoard::try_from(repr).map_err(serde::de::Error::custom)
+    }
+}
+
+impl OthelloBoard {
+    pub const ENCODED_LEN: usize = 17;
+
+    /// Fixed-size encoding: black and white as little-endian `u64`s followed
+    /// by the side to move (`0` Black, `1` White). The ply counter is not
+    /// part of the encoding, so equal positions encode identically.
+    pub const fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+        let mut buf = [0; Self::ENCODED_LEN];
+        let black = self.black.to_le_bytes();
+        let white = self.white.to_le_bytes();
+        let mut i = 0;
+        while i < 8 {
+            buf[i] = black[i];
+            buf[i + 8] = white[i];
+            i += 1;
+        }
+        buf[16] = match self.turn {
+            OthelloColor::Black => 0,
+            OthelloColor::White => 1,
+        };
+        buf
+    }
+
+    /// Decodes [`OthelloBoard::to_bytes`] output. The ply counter is zero.
+    pub fn from_bytes(buf: &[u8; Self::ENCODED_LEN]) -> Result<Self, DecodeBoardError> {
+        let mut black = [0; 8];
+        let mut white = [0; 8];
+        black.copy_from_slice(&buf[..8]);
+        white.copy_from_slice(&buf[8..16]);
+        let turn = match buf[16] {
+            0 => OthelloColor::Black,
+            1 => OthelloColor::White,
+            b => return Err(DecodeBoardError::InvalidTurn(b)),
+        };
+        let (black, white) = (u64::from_le_bytes(black), u64::from_le_bytes(white));
+        if black & white != 0 {
+            return Err(DecodeBoardError::Overlap);
+        }
+        Ok(OthelloBoard::from_bitboards(black, white, turn))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serde_round_trip() {
+        let board = OthelloBoard::from_transcript("f5d6c3").unwrap();
+        let json = serde_json::to_string(&board).unwrap();
+        assert_eq!(
+            json,
+            format!(
+                r#"{{"black":{},"white":{},"turn":"White","ply":3}}"#,
+                board.black, board.white
+            )
+        );
+        assert_eq!(serde_json::from_str::<OthelloBoard>(&json).unwrap(), board);
+    }
+
+    #[test]
+    fn test_serde_rejects_overlap() {
+        let json = r#"{"black":3,"white":2,"turn":"Black","ply":0}"#;
+        let err = serde_json::from_str::<OthelloBoard>(json).unwrap_err();
+        assert!(err.to_string().contains("overlap"));
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let board = OthelloBoard::from_transcript("f5f6").unwrap();
+        let bytes = board.to_bytes();
+        assert_eq!(bytes.len(), 17);
+        assert_eq!(&bytes[..8], &board.black.to_le_bytes());
+        assert_eq!(bytes[16], 0);
+
+        let decoded = OthelloBoard::from_bytes(&bytes).unwrap();
+        assert_eq!((decoded.black, decoded.white), (board.black, board.white));
+        assert_eq!(decoded.get_turn(), board.get_turn());
+        assert_eq!(decoded.get_ply(), 0);
+    }
+
+    #[test]
+    fn test_bytes_rejects_invalid_input() {
+        let mut bytes = OthelloBoard::new().to_bytes();
+        bytes[16] = 2;
+        assert_eq!(
+            OthelloBoard::from_bytes(&bytes),
+            Err(DecodeBoardError::InvalidTurn(2))
+        );
+
+        let mut bytes = OthelloBoard::new().to_bytes();
+        bytes[0] = 1;
+        bytes[8] = 1;
+        assert_eq!(
+            OthelloBoard::from_bytes(&bytes),
+            Err(DecodeBoardError::Overlap)
+        );
+    }
+}
@@ -0,0 +1,618 @@
+//! Generic Game Format, the text format Othello servers such as GGS use to
+//! exchange games:
+//!
+//! ```text
+//! (;GM[Othello]PC[GGS/os]PB[alice]PW[bob]RB[1850.5]RW[1790.0]TI[05:00//02:00]
+//! TY[8]RE[+4.000]BO[8 -------- ... -------- *]B[f5//1.2]W[d6/-2.5/3.4];)
+//! ```
+//!
+//! Only plain 8x8 games are supported. Tags this module does not know are
+//! kept as they are, so a game can be read and written back without loss.
+
+use std::{fmt, str::FromStr, time::Duration};
+
+use crate::{
+    GameRecord, GameStatus, Move, OthelloBoard, OthelloColor, ParseBoardError, ReplayError, Square,
+};
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ParseGgfError {
+    /// Malformed structure at the given byte offset.
+    Syntax(usize),
+    /// `GM` names another game.
+    NotOthello,
+    /// `BO` has a board size other than 8.
+    UnsupportedBoardSize,
+    InvalidBoard(ParseBoardError),
+    InvalidValue {
+        tag: &'static str,
+    },
+    /// Move `index` was made by the side not to move. `index` counts the
+    /// `B` and `W` tags of the file, starting at zero.
+    WrongColor {
+        index: usize,
+    },
+    IllegalMove(ReplayError),
+}
+
+impl fmt::Display for ParseGgfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseGgfError::Syntax(offset) => write!(f, "invalid GGF at byte {offset}"),
+            ParseGgfError::NotOthello => f.write_str("not an Othello game"),
+            ParseGgfError::UnsupportedBoardSize => f.write_str("only 8x8 boards are supported"),
+            ParseGgfError::InvalidBoard(error) => write!(f, "invalid board: {error}"),
+            ParseGgfError::InvalidValue { tag } => write!(f, "invalid value for {tag}"),
+            ParseGgfError::WrongColor { index } => {
+                write!(f, "move {}: played by the side not to move", index + 1)
+            }
+            ParseGgfError::IllegalMove(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ParseGgfError {}
+
+impl From<ReplayError> for ParseGgfError {
+    fn from(error: ReplayError) -> Self {
+        ParseGgfError::IllegalMove(error)
+    }
+}
+
+/// `TI`: main time, then an increment per move and an extension granted
+/// once the clock runs out. Whole seconds.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct TimeControl {
+    pub main: Duration,
+    pub increment: Duration,
+    pub extension: Duration,
+}
+
+/// How a game ended, the suffix of `RE`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Ending {
+    #[default]
+    Normal,
+    /// `:r`
+    Resignation,
+    /// `:t`
+    Timeout,
+    /// `:s`, a score agreed by both players.
+    Agreement,
+}
+
+/// `RE`: the final disc difference from Black's point of view.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct GgfResult {
+    pub score: f64,
+    pub ending: Ending,
+}
+
+/// The evaluation and time recorded with a move, as in `B[f5/1.2/3.4]`.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct MoveDetails {
+    pub eval: Option<f64>,
+    /// Seconds spent on the move.
+    pub time: Option<f64>,
+}
+
+/// One GGF game.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct GgfGame {
+    /// `PC`
+    pub place: Option<String>,
+    /// `DT`, kept as written.
+    pub date: Option<String>,
+    /// `PB`
+    pub black_name: Option<String>,
+    /// `PW`
+    pub white_name: Option<String>,
+    /// `RB`
+    pub black_rating: Option<f64>,
+    /// `RW`
+    pub white_rating: Option<f64>,
+    /// `TI`
+    pub time_control: Option<TimeControl>,
+    /// `TY`, kept as written.
+    pub game_type: Option<String>,
+    /// `RE`
+    pub result: Option<GgfResult>,
+    /// `BO` and the `B`/`W` moves. Passes the file left out are filled in.
+    pub record: GameRecord,
+    /// Details for each move of `record`, in order. Missing entries are
+    /// written as plain moves.
+    pub move_details: Vec<MoveDetails>,
+    /// Tags not listed above, in file order. They are written back after
+    /// `BO`, before the moves, keeping that order.
+    pub extra: Vec<(String, String)>,
+}
+
+impl FromStr for GgfGame {
+    type Err = ParseGgfError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { s, pos: 0 };
+        let game = parser.game()?;
+        parser.skip_whitespace();
+        if parser.pos != s.len() {
+            return Err(ParseGgfError::Syntax(parser.pos));
+        }
+        Ok(game)
+    }
+}
+
+/// Parses every game of a collection, such as one game per line.
+pub fn parse_ggf_games(s: &str) -> Result<Vec<GgfGame>, ParseGgfError> {
+    let mut parser = Parser { s, pos: 0 };
+    let mut games = Vec::new();
+    parser.skip_whitespace();
+    while parser.pos < s.len() {
+        games.push(parser.game()?);
+        parser.skip_whitespace();
+    }
+    Ok(games)
+}
+
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn rest(&self) -> &str {
+        &self.s[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), ParseGgfError> {
+        self.skip_whitespace();
+        if !self.rest().starts_with(token) {
+            return Err(ParseGgfError::Syntax(self.pos));
+        }
+        self.pos += token.len();
+        Ok(())
+    }
+
+    /// Reads `NAME[value]`, unescaping the value.
+    fn tag(&mut self) -> Result<(&str, String), ParseGgfError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let len = self
+            .rest()
+            .find(|c: char| !c.is_ascii_uppercase())
+            .unwrap_or(self.rest().len());
+        if len == 0 {
+            return Err(ParseGgfError::Syntax(start));
+        }
+        self.pos += len;
+        let name = &self.s[start..self.pos];
+        self.expect("[")?;
+
+        let mut value = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                ']' => {
+                    self.pos += offset + 1;
+                    return Ok((name, value));
+                }
+                '\\' => value.extend(chars.next().map(|(_, c)| c)),
+                c => value.push(c),
+            }
+        }
+        Err(ParseGgfError::Syntax(self.s.len()))
+    }
+
+    fn game(&mut self) -> Result<GgfGame, ParseGgfError> {
+        self.expect("(;")?;
+        let mut game = GgfGame::default();
+        let mut initial = OthelloBoard::new();
+        let mut moves = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with(";)") {
+                self.pos += 2;
+                break;
+            }
+            let (name, value) = self.tag()?;
+            match name {
+                "GM" if value.eq_ignore_ascii_case("othello") => {}
+                "GM" => return Err(ParseGgfError::NotOthello),
+                "PC" => game.place = Some(value),
+                "DT" => game.date = Some(value),
+                "PB" => game.black_name = Some(value),
+                "PW" => game.white_name = Some(value),
+                "RB" => game.black_rating = Some(parse_number(&value, "RB")?),
+                "RW" => game.white_rating = Some(parse_number(&value, "RW")?),
+                "TI" => game.time_control = Some(parse_time_control(&value)?),
+                "TY" => game.game_type = Some(value),
+                "RE" => game.result = Some(parse_result(&value)?),
+                "BO" => initial = parse_board(&value)?,
+                "B" => moves.push((OthelloColor::Black, parse_move(&value, "B")?)),
+                "W" => moves.push((OthelloColor::White, parse_move(&value, "W")?)),
+                _ => game.extra.push((name.to_owned(), value)),
+            }
+        }
+
+        game.record = GameRecord::from_position(initial);
+        for (index, (color, (mv, details))) in moves.into_iter().enumerate() {
+            let board = *game.record.board();
+            if board.turn != color {
+                if mv == Move::Pass || board.status() != GameStatus::MustPass {
+                    return Err(ParseGgfError::WrongColor { index });
+                }
+                game.record.play(Move::Pass).map_err(|reason| ReplayError {
+                    index,
+                    mv: Move::Pass,
+                    reason,
+                })?;
+                game.move_details.push(MoveDetails::default());
+            }
+            game.record
+                .play(mv)
+                .map_err(|reason| ReplayError { index, mv, reason })?;
+            game.move_details.push(details);
+        }
+        Ok(game)
+    }
+}
+
+fn parse_number(value: &str, tag: &'static str) -> Result<f64, ParseGgfError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| ParseGgfError::InvalidValue { tag })
+}
+
+/// Parses `[[h:]m:]s`, or an empty string as zero.
+fn parse_duration(value: &str) -> Option<Duration> {
+    if value.is_empty() {
+        return Some(Duration::ZERO);
+    }
+    let mut seconds: u64 = 0;
+    for (index, part) in value.split(':').enumerate() {
+        if index > 2 {
+            return None;
+        }
+        seconds = seconds.checked_mul(60)?.checked_add(part.parse().ok()?)?;
+    }
+    Some(Duration::from_secs(seconds))
+}
+
+fn parse_time_control(value: &str) -> Result<TimeControl, ParseGgfError> {
+    let error = ParseGgfError::InvalidValue { tag: "TI" };
+    let mut parts = value.trim().split('/').map(parse_duration);
+    let mut next = || parts.next().unwrap_or(Some(Duration::ZERO));
+    let (Some(main), Some(increment), Some(extension)) = (next(), next(), next()) else {
+        return Err(error);
+    };
+    if parts.next().is_some() {
+        return Err(error);
+    }
+    Ok(TimeControl {
+        main,
+        increment,
+        extension,
+    })
+}
+
+fn parse_result(value: &str) -> Result<GgfResult, ParseGgfError> {
+    let value = value.trim();
+    let (score, ending) = match value.split_once(':') {
+        None => (value, Ending::Normal),
+        Some((score, "r")) => (score, Ending::Resignation),
+        Some((score, "t")) => (score, Ending::Timeout),
+        Some((score, "s")) => (score, Ending::Agreement),
+        Some(_) => return Err(ParseGgfError::InvalidValue { tag: "RE" }),
+    };
+    Ok(GgfResult {
+        score: parse_number(score, "RE")?,
+        ending,
+    })
+}
+
+fn parse_board(value: &str) -> Result<OthelloBoard, ParseGgfError> {
+    let value = value.trim_start();
+    let (size, position) = value.split_once(char::is_whitespace).unwrap_or((value, ""));
+    if size != "8" {
+        return Err(ParseGgfError::UnsupportedBoardSize);
+    }
+    position.parse().map_err(ParseGgfError::InvalidBoard)
+}
+
+/// Parses `square[/eval[/time]]`, where the square may be `PA` to pass.
+fn parse_move(value: &str, tag: &'static str) -> Result<(Move, MoveDetails), ParseGgfError> {
+    let error = ParseGgfError::InvalidValue { tag };
+    let mut parts = value.trim().split('/');
+    let mv = match parts.next() {
+        Some(pass) if pass.eq_ignore_ascii_case("pa") => Move::Pass,
+        Some(square) => Move::Place(square.parse().map_err(|_| error.clone())?),
+        None => return Err(error),
+    };
+    let mut number = || match parts.next() {
+        None | Some("") => Ok(None),
+        Some(part) => parse_number(part, tag).map(Some),
+    };
+    let details = MoveDetails {
+        eval: number()?,
+        time: number()?,
+    };
+    if parts.next().is_some() {
+        return Err(error);
+    }
+    Ok((mv, details))
+}
+
+/// Writes `NAME[value]`, escaping `]` and `\`.
+fn write_tag(f: &mut fmt::Formatter<'_>, name: &str, value: &str) -> fmt::Result {
+    write!(f, "{name}[")?;
+    for c in value.chars() {
+        if c == ']' || c == '\\' {
+            f.write_str("\\")?;
+        }
+        write!(f, "{c}")?;
+    }
+    f.write_str("]")
+}
+
+impl fmt::Display for TimeControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let duration = |duration: Duration| {
+            let seconds = duration.as_secs();
+            match seconds {
+                0 => String::new(),
+                1..3600 => format!("{:02}:{:02}", seconds / 60, seconds % 60),
+                _ => format!(
+                    "{}:{:02}:{:02}",
+                    seconds / 3600,
+                    seconds / 60 % 60,
+                    seconds % 60
+                ),
+            }
+        };
+        let main = match self.main {
+            Duration::ZERO => "0".to_owned(),
+            main => duration(main),
+        };
+        write!(
+            f,
+            "{main}/{}/{}",
+            duration(self.increment),
+            duration(self.extension)
+        )
+    }
+}
+
+impl fmt::Display for GgfResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:+}", self.score)?;
+        f.write_str(match self.ending {
+            Ending::Normal => "",
+            Ending::Resignation => ":r",
+            Ending::Timeout => ":t",
+            Ending::Agreement => ":s",
+        })
+    }
+}
+
+/// Writes the game on one line, with `BO` always present.
+impl fmt::Display for GgfGame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("(;GM[Othello]")?;
+        let text = [
+            ("PC", &self.place),
+            ("DT", &self.date),
+            ("PB", &self.black_name),
+            ("PW", &self.white_name),
+        ];
+        for (name, value) in text {
+            if let Some(value) = value {
+                write_tag(f, name, value)?;
+            }
+        }
+        for (name, rating) in [("RB", self.black_rating), ("RW", self.white_rating)] {
+            if let Some(rating) = rating {
+                write_tag(f, name, &rating.to_string())?;
+            }
+        }
+        if let Some(time_control) = self.time_control {
+            write_tag(f, "TI", &time_control.to_string())?;
+        }
+        if let Some(game_type) = &self.game_type {
+            write_tag(f, "TY", game_type)?;
+        }
+        if let Some(result) = self.result {
+            write_tag(f, "RE", &result.to_string())?;
+        }
+
+        let initial = self.record.initial();
+        f.write_str("BO[8 ")?;
+        for square in Square::all() {
+            if square.mask() & initial.black != 0 {
+                f.write_str("*")?;
+            } else if square.mask() & initial.white != 0 {
+                f.write_str("O")?;
+            } else {
+                f.write_str("-")?;
+            }
+            if square.file() == 7 {
+                f.write_str(" ")?;
+            }
+        }
+        f.write_str(match initial.turn {
+            OthelloColor::Black => "*]",
+            OthelloColor::White => "O]",
+        })?;
+
+        for (name, value) in &self.extra {
+            write_tag(f, name, value)?;
+        }
+
+        let mut board = *initial;
+        for (index, recorded) in self.record.moves().iter().enumerate() {
+            let name = match board.turn {
+                OthelloColor::Black => "B",
+                OthelloColor::White => "W",
+            };
+            recorded.replay(&mut board);
+            let mut value = match recorded.mv {
+                Move::Place(square) => square.to_string(),
+                Move::Pass => "PA".to_owned(),
+            };
+            let details = self.move_details.get(index).copied().unwrap_or_default();
+            match (details.eval, details.time) {
+                (None, None) => {}
+                (Some(eval), None) => value += &format!("/{eval}"),
+                (eval, Some(time)) => {
+                    let eval = eval.map(|eval| eval.to_string()).unwrap_or_default();
+                    value += &format!("/{eval}/{time}");
+                }
+            }
+            write_tag(f, name, &value)?;
+        }
+        f.write_str(";)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAME: &str = "(;GM[Othello]PC[GGS/os]DT[2003.12.15_13:24:03.MST]PB[alice]PW[bob]\
+        RB[1850.5]RW[1790]TI[05:00//02:00]TY[8]RE[+4.000:r]\
+        BO[8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *]\
+        B[f5//1.25]W[d6/-2.5/3]B[c3]W[d3]B[c4]W[f4]B[f6];)";
+
+    #[test]
+    fn test_parse() {
+        let game: GgfGame = GAME.parse().unwrap();
+        assert_eq!(game.place.as_deref(), Some("GGS/os"));
+        assert_eq!(game.date.as_deref(), Some("2003.12.15_13:24:03.MST"));
+        assert_eq!(game.black_name.as_deref(), Some("alice"));
+        assert_eq!(game.white_name.as_deref(), Some("bob"));
+        assert_eq!(game.black_rating, Some(1850.5));
+        assert_eq!(game.white_rating, Some(1790.0));
+        assert_eq!(
+            game.time_control,
+            Some(TimeControl {
+                main: Duration::from_secs(300),
+                increment: Duration::ZERO,
+                extension: Duration::from_secs(120),
+            })
+        );
+        assert_eq!(game.game_type.as_deref(), Some("8"));
+        assert_eq!(
+            game.result,
+            Some(GgfResult {
+                score: 4.0,
+                ending: Ending::Resignation,
+            })
+        );
+        assert_eq!(*game.record.initial(), OthelloBoard::new());
+        assert_eq!(
+            *game.record.board(),
+            OthelloBoard::from_transcript("f5d6c3d3c4f4f6").unwrap()
+        );
+        assert_eq!(
+            game.move_details[..2],
+            [
+                MoveDetails {
+                    eval: None,
+                    time: Some(1.25),
+                },
+                MoveDetails {
+                    eval: Some(-2.5),
+                    time: Some(3.0),
+                },
+            ]
+        );
+        assert!(game.extra.is_empty());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let game: GgfGame = GAME.parse().unwrap();
+        let written = game.to_string();
+        assert_eq!(written.parse::<GgfGame>().unwrap(), game);
+        assert_eq!(written.parse::<GgfGame>().unwrap().to_string(), written);
+
+        let mut game = GgfGame {
+            place: Some("odd ]name\\".to_owned()),
+            time_control: Some(TimeControl {
+                main: Duration::from_secs(3725),
+                ..TimeControl::default()
+            }),
+            extra: vec![("KM".to_owned(), "0".to_owned())],
+            ..GgfGame::default()
+        };
+        game.record.play(Move::Place(Square::F5)).unwrap();
+        game.move_details.push(MoveDetails::default());
+        let written = game.to_string();
+        assert!(written.contains("PC[odd \\]name\\\\]"));
+        assert!(written.contains("TI[1:02:05//]"));
+        assert_eq!(written.parse::<GgfGame>().unwrap(), game);
+
+        // Results are written without rounding.
+        game.result = Some(GgfResult {
+            score: -4.1234,
+            ending: Ending::Timeout,
+        });
+        let written = game.to_string();
+        assert!(written.contains("RE[-4.1234:t]"));
+        assert_eq!(written.parse::<GgfGame>().unwrap(), game);
+    }
+
+    #[test]
+    fn test_passes() {
+        // Black has no move; White plays d1 twice around Black's pass.
+        let board = "8 O**----- -------- -------- -------- -------- -------- -------- ------OO *";
+        let explicit = format!("(;GM[Othello]BO[{board}]B[PA]W[d1];)");
+        let implicit = format!("(;GM[Othello]BO[{board}]W[d1];)");
+        let explicit: GgfGame = explicit.parse().unwrap();
+        let implicit: GgfGame = implicit.parse().unwrap();
+        assert_eq!(explicit, implicit);
+        assert_eq!(explicit.record.len(), 2);
+        assert!(explicit.to_string().contains("B[PA]W[d1]"));
+    }
+
+    #[test]
+    fn test_errors() {
+        let parse = |s: &str| s.parse::<GgfGame>().unwrap_err();
+        assert_eq!(parse("(;GM[Chess];)"), ParseGgfError::NotOthello);
+        assert_eq!(parse("GM[Othello];)"), ParseGgfError::Syntax(0));
+        assert_eq!(parse("(;GM[Othello]PB[x"), ParseGgfError::Syntax(17));
+        assert_eq!(
+            parse("(;GM[Othello]BO[10 -];)"),
+            ParseGgfError::UnsupportedBoardSize
+        );
+        assert_eq!(
+            parse("(;RB[high];)"),
+            ParseGgfError::InvalidValue { tag: "RB" }
+        );
+        assert_eq!(
+            parse("(;B[f5]B[d6];)"),
+            ParseGgfError::WrongColor { index: 1 }
+        );
+        assert_eq!(
+            parse("(;B[f5]W[a1];)"),
+            ParseGgfError::IllegalMove(ReplayError {
+                index: 1,
+                mv: Move::Place(Square::A1),
+                reason: crate::MoveError::NoFlips,
+            })
+        );
+        assert_eq!(parse("(;;) x"), ParseGgfError::Syntax(5));
+    }
+
+    #[test]
+    fn test_collection() {
+        let games = parse_ggf_games(&format!("{GAME}\n(;GM[Othello]B[f5];)\n")).unwrap();
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[1].record.len(), 1);
+        assert_eq!(parse_ggf_games(" \n").unwrap(), []);
+    }
+}
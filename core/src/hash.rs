@@ -0,0 +1,140 @@
+//! Zobrist position keys.
+//!
+//! The key tables are generated at compile time from a fixed seed, so keys
+//! are identical across platforms and builds and are safe to persist.
+
+use crate::{MoveOutcome, OthelloBoard, OthelloColor};
+
+const SEED: u64 = 0x4f74_6865_6c6c_6f21;
+
+const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    (state, z ^ (z >> 31))
+}
+
+const fn generate() -> ([[u64; 64]; 2], u64) {
+    let mut table = [[0; 64]; 2];
+    let mut state = SEED;
+    let mut color = 0;
+    while color < 2 {
+        let mut square = 0;
+        while square < 64 {
+            let (next, value) = splitmix64(state);
+            state = next;
+            table[color][square] = value;
+            square += 1;
+        }
+        color += 1;
+    }
+    let (_, side) = splitmix64(state);
+    (table, side)
+}
+
+const KEYS: ([[u64; 64]; 2], u64) = generate();
+
+/// Per-square keys indexed by `[color][square]`, Black first.
+pub const SQUARE_KEYS: [[u64; 64]; 2] = KEYS.0;
+
+/// XORed into the key when White is to move.
+pub const WHITE_TO_MOVE_KEY: u64 = KEYS.1;
+
+#[inline]
+const fn color_index(color: OthelloColor) -> usize {
+    match color {
+        OthelloColor::Black => 0,
+        OthelloColor::White => 1,
+    }
+}
+
+#[inline]
+fn mask_key(color: OthelloColor, mut mask: u64) -> u64 {
+    let keys = &SQUARE_KEYS[color_index(color)];
+    let mut key = 0;
+    while mask != 0 {
+        key ^= keys[mask.trailing_zeros() as usize];
+        mask &= mask - 1;
+    }
+    key
+}
+
+impl OthelloBoard {
+    /// Zobrist key of the position. Discs and side to move are hashed; the
+    /// ply counter is not.
+    pub fn hash_key(&self) -> u64 {
+        let mut key =
+            mask_key(OthelloColor::Black, self.black) ^ mask_key(OthelloColor::White, self.white);
+        if self.turn == OthelloColor::White {
+            key ^= WHITE_TO_MOVE_KEY;
+        }
+        key
+    }
+}
+
+/// Returns `key` updated for a move played by `mover`, without rehashing
+/// the whole board.
+#[inline]
+pub fn update_key(key: u64, mover: OthelloColor, outcome: &MoveOutcome) -> u64 {
+    let flipped = outcome.flipped;
+    key ^ mask_key(mover, flipped | outcome.square.mask())
+        ^ mask_key(mover.opponent(), flipped)
+        ^ WHITE_TO_MOVE_KEY
+}
+
+/// Returns `key` updated for a pass.
+#[inline]
+pub const fn update_key_pass(key: u64) -> u64 {
+    key ^ WHITE_TO_MOVE_KEY
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_transcript;
+
+    #[test]
+    fn test_keys_are_stable() {
+        // Pinned so that persisted keys keep working across releases.
+        assert_eq!(SQUARE_KEYS[0][0], 0x6870_d580_c15d_330a);
+        assert_eq!(WHITE_TO_MOVE_KEY, 0xbbd5_e94c_aff0_0d0f);
+        assert_eq!(OthelloBoard::new().hash_key(), 0x89fa_61f9_cbb5_1763);
+    }
+
+    #[test]
+    fn test_transposition_reaches_same_key() {
+        let a = OthelloBoard::from_transcript("c4c3d3c5").unwrap();
+        let b = OthelloBoard::from_transcript("d3c3c4c5").unwrap();
+        assert_eq!((a.black, a.white, a.turn), (b.black, b.white, b.turn));
+        assert_eq!(a.hash_key(), b.hash_key());
+
+        let c = OthelloBoard::from_transcript("c4c3").unwrap();
+        assert_ne!(a.hash_key(), c.hash_key());
+    }
+
+    #[test]
+    fn test_side_to_move_changes_key() {
+        let board = OthelloBoard::new();
+        let mut other = board;
+        other.turn = OthelloColor::White;
+        assert_eq!(board.hash_key() ^ other.hash_key(), WHITE_TO_MOVE_KEY);
+    }
+
+    #[test]
+    fn test_incremental_update_matches_full_hash() {
+        let mut board = OthelloBoard::new();
+        let mut key = board.hash_key();
+        for square in parse_transcript("f5d6c3d3c4f4f6f3e6e7d7c5").unwrap() {
+            let mover = board.get_turn();
+            let outcome = board.try_place(square).unwrap();
+            key = update_key(key, mover, &outcome);
+            assert_eq!(key, board.hash_key());
+        }
+        assert_eq!(update_key_pass(key), {
+            let mut passed = board;
+            passed.turn = passed.turn.opponent();
+            passed.hash_key()
+        });
+    }
+}
@@ -1,3 +1,34 @@
+use std::fmt;
+
+pub mod backend;
+mod bitboard;
+mod codec;
+pub mod ggf;
+pub mod hash;
+mod notation;
+mod perft;
+mod record;
+#[cfg(test)]
+mod reference;
+mod square;
+mod stability;
+mod symmetry;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+mod tree;
+mod undo;
+
+pub use bitboard::{Bitboard, BitboardIter};
+pub use codec::DecodeBoardError;
+pub use notation::{
+    ParseBoardError, ParseSquareError, TranscriptError, format_transcript, parse_transcript,
+};
+pub use record::{GameRecord, RecordedMove, ReplayError};
+pub use square::Square;
+pub use symmetry::Symmetry;
+pub use tree::{Annotation, DepthFirst, GameTree, Node, NodeId, ParseAnnotationError};
+pub use undo::Undo;
+
 #[rustfmt::skip]
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Ord, PartialOrd, Hash)]
 #[derive(enum_table::Enumable, serde::Serialize, serde::Deserialize)]
@@ -6,10 +37,92 @@ pub enum OthelloColor {
     White,
 }
 
+impl OthelloColor {
+    #[inline]
+    pub const fn opponent(self) -> Self {
+        match self {
+            OthelloColor::Black => OthelloColor::White,
+            OthelloColor::White => OthelloColor::Black,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GameStatus {
+    /// The side to move has at least one legal move.
+    Ongoing,
+    /// The side to move has no legal moves but the opponent does.
+    MustPass,
+    /// Neither side can move. `winner` is `None` on a draw.
+    Finished {
+        winner: Option<OthelloColor>,
+        black: u32,
+        white: u32,
+    },
+}
+
+/// A single ply: placing a disc or passing.
+#[rustfmt::skip]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum Move {
+    Place(Square),
+    Pass,
+}
+
+impl From<Square> for Move {
+    #[inline]
+    fn from(value: Square) -> Self {
+        Move::Place(value)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct MoveOutcome {
+    pub square: Square,
+    /// Opponent discs turned over by the move, excluding the placed disc.
+    pub flipped: u64,
+    /// Side to move after the move has been played.
+    pub turn: OthelloColor,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, serde::Serialize, serde::Deserialize)]
+pub enum MoveError {
+    /// The mask passed to a `u64` based API did not have exactly one bit set.
+    InvalidMask,
+    /// The square already holds a disc.
+    Occupied,
+    /// Placing on the square would not flip any opponent disc.
+    NoFlips,
+    /// A pass was requested while the side to move has a legal move, or
+    /// after the game has ended.
+    IllegalPass,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MoveError::InvalidMask => "move mask must have exactly one bit set",
+            MoveError::Occupied => "square is already occupied",
+            MoveError::NoFlips => "move does not flip any disc",
+            MoveError::IllegalPass => "passing is only allowed without a legal move",
+        })
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// Bitboard game state. Prefer [`OthelloBoard::from_bitboards`] over a
+/// struct literal when setting up a position by hand.
+#[derive(PartialEq, Eq, Clone, Copy, Hash)]
 pub struct OthelloBoard {
     pub black: u64,
     pub white: u64,
-    pub turn: u8,
+    /// Side to move.
+    pub turn: OthelloColor,
+    /// Number of plies (moves and passes) played so far. Saturates at
+    /// `u16::MAX`, which only endless null moves can reach.
+    pub ply: u16,
 }
 
 macro_rules! line {
@@ -32,36 +145,41 @@ macro_rules! line {
         result
     }};
 }
+pub(crate) use line;
 
 impl OthelloBoard {
-    const LR_EDGE_MASK: u64 = 0x7e7e7e7e7e7e7e7e;
-    const TB_EDGE_MASK: u64 = 0x00FFFFFFFFFFFF00;
-    const LTRB_EDGE_MASK: u64 = 0x007e7e7e7e7e7e00;
-    const SHIFT_MASK_LIST: [(u32, u64); 4] = [
-        (1, Self::LR_EDGE_MASK),
-        (8, Self::TB_EDGE_MASK),
-        (7, Self::LTRB_EDGE_MASK),
-        (9, Self::LTRB_EDGE_MASK),
-    ];
-
     #[allow(clippy::new_without_default)]
     pub const fn new() -> Self {
         OthelloBoard {
             black: 0x0000000810000000,
             white: 0x0000001008000000,
-            turn: 0,
+            turn: OthelloColor::Black,
+            ply: 0,
         }
     }
 
+    /// Builds a position from raw bitboards with `turn` to move and the ply
+    /// counter reset to zero.
     #[inline]
-    pub const fn get_turn(&self) -> OthelloColor {
-        if self.turn % 2 == 0 {
-            OthelloColor::Black
-        } else {
-            OthelloColor::White
+    pub const fn from_bitboards(black: u64, white: u64, turn: OthelloColor) -> Self {
+        OthelloBoard {
+            black,
+            white,
+            turn,
+            ply: 0,
         }
     }
 
+    #[inline]
+    pub const fn get_turn(&self) -> OthelloColor {
+        self.turn
+    }
+
+    #[inline]
+    pub const fn get_ply(&self) -> u16 {
+        self.ply
+    }
+
     #[inline]
     pub const fn get_current_player_and_opponent(&self) -> (u64, u64) {
         match self.get_turn() {
@@ -72,20 +190,75 @@ impl OthelloBoard {
 
     pub fn legal_moves(&self) -> u64 {
         let (player, opponent) = self.get_current_player_and_opponent();
-        let empty_squares = !(player | opponent);
+        Self::legal_moves_of(player, opponent)
+    }
+
+    /// Legal moves as a [`Bitboard`], for iterating over [`Square`]s.
+    /// Use [`OthelloBoard::legal_moves`] for the raw mask.
+    #[inline]
+    pub fn legal_squares(&self) -> Bitboard {
+        Bitboard(self.legal_moves())
+    }
+
+    #[inline]
+    fn legal_moves_of(player: u64, opponent: u64) -> u64 {
+        backend::active::legal_moves(player, opponent)
+    }
+
+    #[inline]
+    pub const fn count(&self, color: OthelloColor) -> u32 {
+        match color {
+            OthelloColor::Black => self.black.count_ones(),
+            OthelloColor::White => self.white.count_ones(),
+        }
+    }
+
+    pub fn status(&self) -> GameStatus {
+        let (player, opponent) = self.get_current_player_and_opponent();
+        if Self::legal_moves_of(player, opponent) != 0 {
+            return GameStatus::Ongoing;
+        }
+        if Self::legal_moves_of(opponent, player) != 0 {
+            return GameStatus::MustPass;
+        }
 
-        let mut legal = 0;
-        for (shift, mask) in Self::SHIFT_MASK_LIST {
-            // Moves in the "positive" direction (e.g., right, down)
-            let mut line = line!(opponent & mask, player, >>, shift);
-            legal |= line >> shift;
+        let black = self.count(OthelloColor::Black);
+        let white = self.count(OthelloColor::White);
+        let winner = match black.cmp(&white) {
+            std::cmp::Ordering::Greater => Some(OthelloColor::Black),
+            std::cmp::Ordering::Less => Some(OthelloColor::White),
+            std::cmp::Ordering::Equal => None,
+        };
+        GameStatus::Finished {
+            winner,
+            black,
+            white,
+        }
+    }
 
-            // Moves in the "negative" direction (e.g., left, up)
-            line = line!(opponent & mask, player, <<, shift);
-            legal |= line << shift;
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        matches!(self.status(), GameStatus::Finished { .. })
+    }
+
+    /// Hands the turn to the opponent. Only allowed when the side to move
+    /// has no legal moves and the game is not over.
+    pub fn pass(&mut self) -> bool {
+        if self.status() != GameStatus::MustPass {
+            return false;
         }
+        self.advance();
+        true
+    }
 
-        legal & empty_squares
+    /// Plays a [`Move`]. Returns the outcome of a placement, or `None` for
+    /// a pass.
+    pub fn play(&mut self, mv: Move) -> Result<Option<MoveOutcome>, MoveError> {
+        match mv {
+            Move::Place(square) => self.try_place(square).map(Some),
+            Move::Pass if self.pass() => Ok(None),
+            Move::Pass => Err(MoveError::IllegalPass),
+        }
     }
 
     #[inline]
@@ -95,49 +268,39 @@ impl OthelloBoard {
 
     #[inline]
     pub fn can_place_pos(&self, x: u8, y: u8) -> bool {
-        if x >= 8 || y >= 8 {
-            return false;
-        }
-        let pos = 1u64 << (y * 8 + x);
-        self.can_place(pos)
+        Square::from_coords(x, y).is_some_and(|square| self.can_place_square(square))
+    }
+
+    #[inline]
+    pub fn can_place_square(&self, square: Square) -> bool {
+        self.legal_moves() & square.mask() != 0
     }
 
     pub fn place_and_return_inversions(&mut self, pos: u64) -> u64 {
-        let turn = self.get_turn();
-        let player_before = match turn {
-            OthelloColor::Black => self.black,
-            OthelloColor::White => self.white,
-        };
-        if !self.place(pos) {
-            return 0;
-        }
-        let player_after = match turn {
-            OthelloColor::Black => self.black,
-            OthelloColor::White => self.white,
-        };
-        player_before ^ player_after ^ pos
+        self.try_place_mask(pos)
+            .map_or(0, |outcome| outcome.flipped)
     }
 
     pub fn place(&mut self, pos: u64) -> bool {
-        if !self.can_place(pos) {
-            return false;
-        }
+        self.try_place_mask(pos).is_ok()
+    }
 
-        let (mut player, mut opponent) = self.get_current_player_and_opponent();
+    #[inline]
+    pub fn try_place_mask(&mut self, pos: u64) -> Result<MoveOutcome, MoveError> {
+        let square = Square::from_mask(pos).ok_or(MoveError::InvalidMask)?;
+        self.try_place(square)
+    }
 
-        let mut to_flip = 0;
-        for (shift, mask) in Self::SHIFT_MASK_LIST {
-            // Flips in the "positive" direction
-            let line1 = line!(opponent & mask, pos, >>, shift);
-            if ((line1 >> shift) & player) != 0 {
-                to_flip |= line1;
-            }
+    pub fn try_place(&mut self, square: Square) -> Result<MoveOutcome, MoveError> {
+        let pos = square.mask();
+        let (mut player, mut opponent) = self.get_current_player_and_opponent();
+        if (player | opponent) & pos != 0 {
+            return Err(MoveError::Occupied);
+        }
 
-            // Flips in the "negative" direction
-            let line2 = line!(opponent & mask, pos, <<, shift);
-            if ((line2 << shift) & player) != 0 {
-                to_flip |= line2;
-            }
+        let to_flip = Self::flips(player, opponent, pos);
+        if to_flip == 0 {
+            return Err(MoveError::NoFlips);
         }
 
         player ^= to_flip | pos;
@@ -153,8 +316,23 @@ impl OthelloBoard {
                 self.black = opponent;
             }
         }
-        self.turn += 1;
-        true
+        self.advance();
+        Ok(MoveOutcome {
+            square,
+            flipped: to_flip,
+            turn: self.turn,
+        })
+    }
+
+    #[inline]
+    fn flips(player: u64, opponent: u64, pos: u64) -> u64 {
+        backend::active::flips(player, opponent, pos)
+    }
+
+    #[inline]
+    fn advance(&mut self) {
+        self.turn = self.turn.opponent();
+        self.ply = self.ply.saturating_add(1);
     }
 }
 
@@ -194,13 +372,49 @@ mod tests {
         assert_eq!(board.white, 1 << 27);
     }
 
+    fn mask(squares: &[Square]) -> u64 {
+        squares.iter().copied().collect::<Bitboard>().bits()
+    }
+
+    #[test]
+    fn test_legal_squares() {
+        let board = OthelloBoard::new();
+        let moves: Vec<Square> = board.legal_squares().into_iter().collect();
+        assert_eq!(moves, [Square::D3, Square::C4, Square::F5, Square::E6]);
+        assert!(board.can_place_square(Square::C4));
+        assert!(!board.can_place_square(Square::A1));
+    }
+
+    #[test]
+    fn test_flips_by_square() {
+        // Each line direction, through the Square API.
+        let cases = [
+            (Square::A1, [Square::B1, Square::C1, Square::D1], Square::E1),
+            (Square::A1, [Square::A2, Square::A3, Square::A4], Square::A5),
+            (Square::A1, [Square::B2, Square::C3, Square::D4], Square::E5),
+            (Square::H1, [Square::G2, Square::F3, Square::E4], Square::D5),
+        ];
+        for (anchor, line, target) in cases {
+            let mut board =
+                OthelloBoard::from_bitboards(anchor.mask(), mask(&line), OthelloColor::Black);
+            assert_eq!(
+                board.legal_squares().into_iter().collect::<Vec<_>>(),
+                [target]
+            );
+            let outcome = board.try_place(target).unwrap();
+            assert_eq!(outcome.flipped, mask(&line));
+            assert_eq!(board.black, mask(&line) | anchor.mask() | target.mask());
+            assert_eq!(board.white, 0);
+        }
+    }
+
     #[test]
     fn test_flip_horizontal() {
-        let mut board = OthelloBoard {
-            black: 1 << 0,                         // a1
-            white: (1 << 1) | (1 << 2) | (1 << 3), // b1, c1, d1
-            turn: 0,                               // Black's turn
-        };
+        let mut board = OthelloBoard::from_bitboards(
+            1 << 0,                         // a1
+            (1 << 1) | (1 << 2) | (1 << 3), // b1, c1, d1
+            OthelloColor::Black,
+        );
         // Legal move for black should be e1 (1<<4)
         assert_eq!(board.legal_moves(), 1 << 4);
         assert!(board.place(1 << 4));
@@ -214,11 +428,11 @@ mod tests {
 
     #[test]
     fn test_flip_vertical() {
-        let mut board = OthelloBoard {
-            black: 1 << 0,                           // a1
-            white: (1 << 8) | (1 << 16) | (1 << 24), // a2, a3, a4
-            turn: 0,                                 // Black's turn
-        };
+        let mut board = OthelloBoard::from_bitboards(
+            1 << 0,                           // a1
+            (1 << 8) | (1 << 16) | (1 << 24), // a2, a3, a4
+            OthelloColor::Black,
+        );
         // Legal move for black should be a5 (1<<32)
         assert_eq!(board.legal_moves(), 1 << 32);
         assert!(board.place(1 << 32));
@@ -231,11 +445,11 @@ mod tests {
 
     #[test]
     fn test_flip_diagonal_a1_h8() {
-        let mut board = OthelloBoard {
-            black: 1 << 0,                           // a1
-            white: (1 << 9) | (1 << 18) | (1 << 27), // b2, c3, d4
-            turn: 0,                                 // Black's turn
-        };
+        let mut board = OthelloBoard::from_bitboards(
+            1 << 0,                           // a1
+            (1 << 9) | (1 << 18) | (1 << 27), // b2, c3, d4
+            OthelloColor::Black,
+        );
         // Legal move for black should be e5 (1<<36)
         assert_eq!(board.legal_moves(), 1 << 36);
         assert!(board.place(1 << 36));
@@ -248,11 +462,11 @@ mod tests {
 
     #[test]
     fn test_flip_diagonal_h1_a8() {
-        let mut board = OthelloBoard {
-            black: 1 << 7,                            // h1
-            white: (1 << 14) | (1 << 21) | (1 << 28), // g2, f3, e4
-            turn: 0,                                  // Black's turn
-        };
+        let mut board = OthelloBoard::from_bitboards(
+            1 << 7,                            // h1
+            (1 << 14) | (1 << 21) | (1 << 28), // g2, f3, e4
+            OthelloColor::Black,
+        );
         // Legal move for black should be d5 (1<<35)
         assert_eq!(board.legal_moves(), 1 << 35);
         assert!(board.place(1 << 35));
@@ -301,4 +515,147 @@ mod tests {
         assert_eq!(board.black, expected_black2);
         assert_eq!(board.white, expected_white2);
     }
+
+    #[test]
+    fn test_initial_status() {
+        let mut board = OthelloBoard::new();
+        assert_eq!(board.status(), GameStatus::Ongoing);
+        // Passing is not allowed while legal moves exist.
+        assert!(!board.pass());
+        assert_eq!(board.get_turn(), OthelloColor::Black);
+    }
+
+    #[test]
+    fn test_must_pass() {
+        let mut board = OthelloBoard::from_bitboards(
+            (1 << 1) | (1 << 2), // b1, c1
+            1 << 0,              // a1
+            OthelloColor::Black,
+        );
+        // Black cannot outflank the lone white disc in the corner.
+        assert_eq!(board.legal_moves(), 0);
+        assert_eq!(board.status(), GameStatus::MustPass);
+
+        assert!(board.pass());
+        assert_eq!(board.get_turn(), OthelloColor::White);
+        assert_eq!(board.get_ply(), 1);
+        // White can capture along the first rank at d1.
+        assert_eq!(board.legal_moves(), 1 << 3);
+        assert_eq!(board.status(), GameStatus::Ongoing);
+        assert!(board.place(1 << 3));
+        assert_eq!(board.white, 0b1111);
+        assert_eq!(board.black, 0);
+        assert_eq!(board.get_turn(), OthelloColor::Black);
+        assert_eq!(board.get_ply(), 2);
+    }
+
+    #[test]
+    fn test_ply_saturates() {
+        let mut board = OthelloBoard::new();
+        board.ply = u16::MAX;
+        assert!(board.place(Square::F5.mask()));
+        assert_eq!(board.get_ply(), u16::MAX);
+        assert_eq!(board.get_turn(), OthelloColor::White);
+    }
+
+    #[test]
+    fn test_try_place() {
+        let mut board = OthelloBoard::new();
+        assert_eq!(
+            board.try_place(Square::F5),
+            Ok(MoveOutcome {
+                square: Square::F5,
+                flipped: Square::E5.mask(),
+                turn: OthelloColor::White,
+            })
+        );
+
+        let before = board;
+        // d4 is occupied by White.
+        assert_eq!(board.try_place(Square::D4), Err(MoveError::Occupied));
+        // a1 is empty but flanks nothing.
+        assert_eq!(board.try_place(Square::A1), Err(MoveError::NoFlips));
+        assert_eq!(board.try_place_mask(0), Err(MoveError::InvalidMask));
+        assert_eq!(
+            board.try_place_mask(mask(&[Square::F4, Square::D6])),
+            Err(MoveError::InvalidMask)
+        );
+        assert_eq!(board, before);
+    }
+
+    #[test]
+    fn test_play() {
+        let mut board = OthelloBoard::new();
+        assert_eq!(board.play(Move::Pass), Err(MoveError::IllegalPass));
+        let outcome = board.play(Move::Place(Square::F5)).unwrap();
+        assert_eq!(outcome.map(|o| o.flipped), Some(Square::E5.mask()));
+
+        let mut board = OthelloBoard::from_bitboards(
+            mask(&[Square::B1, Square::C1]),
+            Square::A1.mask(),
+            OthelloColor::Black,
+        );
+        assert_eq!(board.play(Square::D1.into()), Err(MoveError::NoFlips));
+        assert_eq!(board.play(Move::Pass), Ok(None));
+        assert_eq!(board.get_turn(), OthelloColor::White);
+    }
+
+    #[test]
+    fn test_finished_by_double_pass() {
+        // Black: a1, White: h8 — nobody can outflank anybody.
+        let mut board = OthelloBoard::from_bitboards(1 << 0, 1 << 63, OthelloColor::Black);
+        assert_eq!(
+            board.status(),
+            GameStatus::Finished {
+                winner: None,
+                black: 1,
+                white: 1,
+            }
+        );
+        assert!(board.is_finished());
+        assert!(!board.pass());
+        assert_eq!(board.play(Move::Pass), Err(MoveError::IllegalPass));
+    }
+
+    #[test]
+    fn test_finished_when_one_color_is_wiped_out() {
+        let mut board = OthelloBoard::from_bitboards(
+            1 << 0,                         // a1
+            (1 << 1) | (1 << 2) | (1 << 3), // b1, c1, d1
+            OthelloColor::Black,
+        );
+        assert!(board.place(1 << 4));
+        assert_eq!(
+            board.status(),
+            GameStatus::Finished {
+                winner: Some(OthelloColor::Black),
+                black: 5,
+                white: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_finished_on_full_board() {
+        // Black owns b1..g4, White owns the rest except the empty a1.
+        let full = u64::MAX;
+        let black = 0x0000_0000_7FFF_FFFE;
+        let mut board =
+            OthelloBoard::from_bitboards(black, full & !black & !1, OthelloColor::White);
+        // White takes a1, flipping a2..a4 and b2..d4.
+        assert_eq!(board.status(), GameStatus::Ongoing);
+        assert_eq!(board.legal_moves(), 1);
+        assert!(board.place(1));
+        assert_eq!(board.black | board.white, full);
+        let GameStatus::Finished {
+            winner,
+            black,
+            white,
+        } = board.status()
+        else {
+            panic!("game should be finished on a full board");
+        };
+        assert_eq!((black, white), (24, 40));
+        assert_eq!(winner, Some(OthelloColor::White));
+    }
 }
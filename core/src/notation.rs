@@ -0,0 +1,467 @@
+use std::{fmt, str::FromStr};
+
+use crate::{GameStatus, Move, MoveError, OthelloBoard, OthelloColor, Square};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ParseSquareError;
+
+impl fmt::Display for ParseSquareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("expected a square from a1 to h8")
+    }
+}
+
+impl std::error::Error for ParseSquareError {}
+
+impl FromStr for Square {
+    type Err = ParseSquareError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let &[file, rank] = s.as_bytes() else {
+            return Err(ParseSquareError);
+        };
+        let x = file.to_ascii_lowercase().wrapping_sub(b'a');
+        let y = rank.wrapping_sub(b'1');
+        Square::from_coords(x, y).ok_or(ParseSquareError)
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            (b'a' + self.file()) as char,
+            (b'1' + self.rank()) as char
+        )
+    }
+}
+
+/// Parses a square such as `f5`, or `pass` (also `ps` or `--`).
+impl FromStr for Move {
+    type Err = ParseSquareError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("pass") || s.eq_ignore_ascii_case("ps") || s == "--" {
+            return Ok(Move::Pass);
+        }
+        s.parse().map(Move::Place)
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Move::Place(square) => write!(f, "{square}"),
+            Move::Pass => f.write_str("pass"),
+        }
+    }
+}
+
+/// Error from parsing or replaying a transcript. `index` counts moves, not
+/// characters, starting at zero.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TranscriptError {
+    InvalidSquare {
+        index: usize,
+    },
+    IllegalMove {
+        index: usize,
+        square: Square,
+        reason: MoveError,
+    },
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranscriptError::InvalidSquare { index } => {
+                write!(f, "move {}: invalid square", index + 1)
+            }
+            TranscriptError::IllegalMove {
+                index,
+                square,
+                reason,
+            } => write!(f, "move {} ({square}): {reason}", index + 1),
+        }
+    }
+}
+
+impl std::error::Error for TranscriptError {}
+
+/// Parses a concatenated move string such as `f5d6c3d3c4f4`. Whitespace
+/// between moves is ignored and files may be upper or lower case.
+pub fn parse_transcript(transcript: &str) -> Result<Vec<Square>, TranscriptError> {
+    let chars: Vec<char> = transcript.chars().filter(|c| !c.is_whitespace()).collect();
+    chars
+        .chunks(2)
+        .enumerate()
+        .map(|(index, pair)| {
+            pair.iter()
+                .collect::<String>()
+                .parse()
+                .map_err(|_| TranscriptError::InvalidSquare { index })
+        })
+        .collect()
+}
+
+pub fn format_transcript(moves: impl IntoIterator<Item = Square>) -> String {
+    moves.into_iter().map(|square| square.to_string()).collect()
+}
+
+impl OthelloBoard {
+    /// Replays `moves` from the current position, passing automatically
+    /// whenever the side to move has no legal move. On error the board is
+    /// left at the position before the offending move.
+    pub fn play_moves(
+        &mut self,
+        moves: impl IntoIterator<Item = Square>,
+    ) -> Result<(), TranscriptError> {
+        self.play_moves_with(moves, |_| {})
+    }
+
+    /// Like [`OthelloBoard::play_moves`], calling `visit` with every
+    /// position reached, including the one after each forced pass.
+    pub fn play_moves_with(
+        &mut self,
+        moves: impl IntoIterator<Item = Square>,
+        mut visit: impl FnMut(&OthelloBoard),
+    ) -> Result<(), TranscriptError> {
+        for (index, square) in moves.into_iter().enumerate() {
+            // Work on a copy so a failed move also undoes its forced pass.
+            let mut next = *self;
+            let passed = next.status() == GameStatus::MustPass && next.pass();
+            let after_pass = next;
+            next.try_place(square)
+                .map_err(|reason| TranscriptError::IllegalMove {
+                    index,
+                    square,
+                    reason,
+                })?;
+            if passed {
+                visit(&after_pass);
+            }
+            visit(&next);
+            *self = next;
+        }
+        Ok(())
+    }
+
+    /// Parses and replays a transcript, see [`OthelloBoard::play_moves`].
+    pub fn play_transcript(&mut self, transcript: &str) -> Result<(), TranscriptError> {
+        let moves = parse_transcript(transcript)?;
+        self.play_moves(moves)
+    }
+
+    /// Builds the position reached by a transcript from the initial board.
+    pub fn from_transcript(transcript: &str) -> Result<Self, TranscriptError> {
+        let mut board = OthelloBoard::new();
+        board.play_transcript(transcript)?;
+        Ok(board)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ParseBoardError {
+    /// The position did not have 64 squares followed by a side to move.
+    InvalidLength,
+    InvalidSquare(char),
+    InvalidTurn(char),
+}
+
+impl fmt::Display for ParseBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseBoardError::InvalidLength => {
+                f.write_str("expected 64 squares followed by the side to move")
+            }
+            ParseBoardError::InvalidSquare(c) => write!(f, "invalid square character {c:?}"),
+            ParseBoardError::InvalidTurn(c) => write!(f, "invalid side to move {c:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseBoardError {}
+
+impl OthelloColor {
+    /// `X` for Black and `O` for White, as used by the text board format.
+    #[inline]
+    pub const fn symbol(self) -> char {
+        match self {
+            OthelloColor::Black => 'X',
+            OthelloColor::White => 'O',
+        }
+    }
+
+    #[inline]
+    pub const fn from_symbol(c: char) -> Option<Self> {
+        match c {
+            'X' | 'x' | '*' => Some(OthelloColor::Black),
+            'O' | 'o' => Some(OthelloColor::White),
+            _ => None,
+        }
+    }
+}
+
+impl OthelloBoard {
+    fn symbol_at(&self, square: Square) -> char {
+        if self.black & square.mask() != 0 {
+            OthelloColor::Black.symbol()
+        } else if self.white & square.mask() != 0 {
+            OthelloColor::White.symbol()
+        } else {
+            '-'
+        }
+    }
+}
+
+/// Parses the 64-character position format: squares from a1 to h8 as `X`
+/// (Black), `O` (White) or `-` (empty), then the side to move. Whitespace
+/// is ignored. The ply counter of the result is zero.
+impl FromStr for OthelloBoard {
+    type Err = ParseBoardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars().filter(|c| !c.is_whitespace());
+        let mut black = 0;
+        let mut white = 0;
+        for square in Square::all() {
+            let c = chars.next().ok_or(ParseBoardError::InvalidLength)?;
+            match OthelloColor::from_symbol(c) {
+                Some(OthelloColor::Black) => black |= square.mask(),
+                Some(OthelloColor::White) => white |= square.mask(),
+                None if c == '-' || c == '.' => {}
+                None => return Err(ParseBoardError::InvalidSquare(c)),
+            }
+        }
+        let c = chars.next().ok_or(ParseBoardError::InvalidLength)?;
+        let turn = OthelloColor::from_symbol(c).ok_or(ParseBoardError::InvalidTurn(c))?;
+        if chars.next().is_some() {
+            return Err(ParseBoardError::InvalidLength);
+        }
+        Ok(OthelloBoard::from_bitboards(black, white, turn))
+    }
+}
+
+impl fmt::Display for OthelloBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for square in Square::all() {
+            fmt::Write::write_char(f, self.symbol_at(square))?;
+        }
+        write!(f, " {}", self.turn.symbol())
+    }
+}
+
+impl fmt::Debug for OthelloBoard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  a b c d e f g h")?;
+        for rank in 0..8 {
+            write!(f, "{}", rank + 1)?;
+            for file in 0..8 {
+                if let Some(square) = Square::from_coords(file, rank) {
+                    write!(f, " {}", self.symbol_at(square))?;
+                }
+            }
+            writeln!(f)?;
+        }
+        write!(f, "{} to move, ply {}", self.turn.symbol(), self.ply)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_square_round_trip() {
+        for square in Square::all() {
+            assert_eq!(square.to_string().parse(), Ok(square));
+        }
+        assert_eq!("F5".parse(), Ok(Square::F5));
+        assert_eq!(Square::A1.to_string(), "a1");
+        assert_eq!(Square::H8.to_string(), "h8");
+        assert_eq!("i1".parse::<Square>(), Err(ParseSquareError));
+        assert_eq!("a9".parse::<Square>(), Err(ParseSquareError));
+        assert_eq!("a".parse::<Square>(), Err(ParseSquareError));
+        assert_eq!("a10".parse::<Square>(), Err(ParseSquareError));
+    }
+
+    #[test]
+    fn test_move_round_trip() {
+        assert_eq!("f5".parse(), Ok(Move::Place(Square::F5)));
+        assert_eq!("PASS".parse(), Ok(Move::Pass));
+        assert_eq!("--".parse(), Ok(Move::Pass));
+        assert_eq!(Move::Pass.to_string(), "pass");
+        assert_eq!(Move::Place(Square::C4).to_string(), "c4");
+    }
+
+    #[test]
+    fn test_parse_and_format_transcript() {
+        let moves = parse_transcript("f5d6C3 d3").unwrap();
+        assert_eq!(moves, [Square::F5, Square::D6, Square::C3, Square::D3]);
+        assert_eq!(format_transcript(moves), "f5d6c3d3");
+
+        assert_eq!(
+            parse_transcript("f5d6z3"),
+            Err(TranscriptError::InvalidSquare { index: 2 })
+        );
+        assert_eq!(
+            parse_transcript("f5d"),
+            Err(TranscriptError::InvalidSquare { index: 1 })
+        );
+    }
+
+    #[test]
+    fn test_replay_transcript() {
+        let board = OthelloBoard::from_transcript("f5d6c3d3c4f4").unwrap();
+        assert_eq!(board.get_ply(), 6);
+        assert_eq!(board.get_turn(), OthelloColor::Black);
+        assert_eq!(
+            board.count(OthelloColor::Black) + board.count(OthelloColor::White),
+            10
+        );
+    }
+
+    #[test]
+    fn test_replay_reports_first_illegal_move() {
+        let mut board = OthelloBoard::new();
+        let err = board.play_transcript("f5d6a1c3").unwrap_err();
+        assert_eq!(
+            err,
+            TranscriptError::IllegalMove {
+                index: 2,
+                square: Square::A1,
+                reason: MoveError::NoFlips,
+            }
+        );
+        assert_eq!(board, OthelloBoard::from_transcript("f5d6").unwrap());
+
+        assert_eq!(
+            OthelloBoard::from_transcript("f5f5"),
+            Err(TranscriptError::IllegalMove {
+                index: 1,
+                square: Square::F5,
+                reason: MoveError::Occupied,
+            })
+        );
+    }
+
+    #[test]
+    fn test_replay_inserts_passes() {
+        // After c1 White has no reply, so Black plays c3 straight away.
+        let mut board = OthelloBoard::from_bitboards(
+            Square::A1.mask(),
+            Square::B1.mask() | Square::B2.mask(),
+            OthelloColor::Black,
+        );
+        board.play_moves([Square::C1]).unwrap();
+        assert_eq!(board.get_turn(), OthelloColor::White);
+        assert_eq!(board.legal_moves(), 0);
+        board.play_moves([Square::C3]).unwrap();
+        assert_eq!(board.get_ply(), 3);
+        assert_eq!(board.white, 0);
+    }
+
+    #[test]
+    fn test_failed_move_after_pass_keeps_board() {
+        // White must pass after c1, but d4 is not a legal move for Black.
+        let mut board = OthelloBoard::from_bitboards(
+            Square::A1.mask(),
+            Square::B1.mask() | Square::B2.mask(),
+            OthelloColor::Black,
+        );
+        board.play_moves([Square::C1]).unwrap();
+        let before = board;
+        assert_eq!(
+            board.play_moves([Square::D4]),
+            Err(TranscriptError::IllegalMove {
+                index: 0,
+                square: Square::D4,
+                reason: MoveError::NoFlips,
+            })
+        );
+        assert_eq!(board, before);
+    }
+
+    #[test]
+    fn test_play_moves_visits_passes() {
+        let mut board = OthelloBoard::from_bitboards(
+            Square::A1.mask(),
+            Square::B1.mask() | Square::B2.mask(),
+            OthelloColor::Black,
+        );
+        let mut turns = Vec::new();
+        board
+            .play_moves_with([Square::C1, Square::C3], |position| {
+                turns.push((position.get_ply(), position.get_turn()))
+            })
+            .unwrap();
+        assert_eq!(
+            turns,
+            [
+                (1, OthelloColor::White),
+                (2, OthelloColor::Black),
+                (3, OthelloColor::White),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_board_text_round_trip() {
+        let board = OthelloBoard::new();
+        let text = board.to_string();
+        assert_eq!(
+            text,
+            "---------------------------OX------XO--------------------------- X"
+        );
+        assert_eq!(text.parse(), Ok(board));
+
+        let board = OthelloBoard::from_transcript("f5d6c3").unwrap();
+        let parsed: OthelloBoard = board.to_string().parse().unwrap();
+        assert_eq!((parsed.black, parsed.white), (board.black, board.white));
+        assert_eq!(parsed.get_turn(), OthelloColor::White);
+    }
+
+    #[test]
+    fn test_board_text_errors() {
+        let empty = "-".repeat(64);
+        assert_eq!(
+            format!("{empty}X").parse::<OthelloBoard>().map(|b| b.black),
+            Ok(0)
+        );
+        assert_eq!(
+            empty.parse::<OthelloBoard>(),
+            Err(ParseBoardError::InvalidLength)
+        );
+        assert_eq!(
+            format!("{empty}XX").parse::<OthelloBoard>(),
+            Err(ParseBoardError::InvalidLength)
+        );
+        assert_eq!(
+            format!("{empty}?").parse::<OthelloBoard>(),
+            Err(ParseBoardError::InvalidTurn('?'))
+        );
+        assert_eq!(
+            format!("#{} X", &empty[1..]).parse::<OthelloBoard>(),
+            Err(ParseBoardError::InvalidSquare('#'))
+        );
+    }
+
+    #[test]
+    fn test_board_debug_grid() {
+        let expected = [
+            "  a b c d e f g h",
+            "1 - - - - - - - -",
+            "2 - - - - - - - -",
+            "3 - - - - - - - -",
+            "4 - - - O X - - -",
+            "5 - - - X O - - -",
+            "6 - - - - - - - -",
+            "7 - - - - - - - -",
+            "8 - - - - - - - -",
+            "X to move, ply 0",
+        ]
+        .join("\n");
+        assert_eq!(format!("{:?}", OthelloBoard::new()), expected);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{GameStatus, OthelloBoard};
+
+impl OthelloBoard {
+    /// Counts the leaf nodes of the game tree `depth` plies deep.
+    ///
+    /// A forced pass counts as a ply, and a finished game counts as a single
+    /// leaf no matter how much depth is left. This matches the published
+    /// Othello perft figures.
+    pub fn perft(&self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let moves = self.legal_moves();
+        if moves == 0 {
+            return match self.status() {
+                GameStatus::Finished { .. } => 1,
+                _ => {
+                    let mut next = *self;
+                    next.pass();
+                    next.perft(depth - 1)
+                }
+            };
+        }
+        if depth == 1 {
+            return moves.count_ones() as u64;
+        }
+
+        let mut nodes = 0;
+        for square in self.legal_squares() {
+            let mut next = *self;
+            if next.try_place(square).is_ok() {
+                nodes += next.perft(depth - 1);
+            }
+        }
+        nodes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Move, OthelloColor, reference::ReferenceBoard, testing::random_games};
+
+    const PERFT: [u64; 12] = [
+        1, 4, 12, 56, 244, 1396, 8200, 55092, 390216, 3005288, 24571284, 212258800,
+    ];
+
+    #[test]
+    fn test_perft_shallow() {
+        let board = OthelloBoard::new();
+        for (depth, &expected) in PERFT.iter().enumerate().take(9) {
+            assert_eq!(board.perft(depth as u32), expected, "depth {depth}");
+        }
+    }
+
+    #[test]
+    fn test_perft_first_passes() {
+        // Depth 9 is the first depth where games can end.
+        assert_eq!(OthelloBoard::new().perft(9), PERFT[9]);
+    }
+
+    #[test]
+    #[ignore = "slow in debug builds"]
+    fn test_perft_deep() {
+        let board = OthelloBoard::new();
+        assert_eq!(board.perft(10), PERFT[10]);
+        assert_eq!(board.perft(11), PERFT[11]);
+    }
+
+    #[test]
+    fn test_perft_finished_game_is_one_leaf() {
+        let board = OthelloBoard::from_bitboards(1, 1 << 63, OthelloColor::Black);
+        assert_eq!(board.perft(5), 1);
+    }
+
+    #[test]
+    fn test_random_games_match_reference() {
+        for game in random_games(1).take(500) {
+            let mut reference = ReferenceBoard::from(&game.positions[0]);
+            for (window, mv) in game.positions.windows(2).zip(&game.moves) {
+                let (board, next) = (window[0], window[1]);
+                assert_eq!(board.legal_moves(), reference.legal_moves(), "{board:?}");
+                match *mv {
+                    Move::Place(square) => {
+                        let mut copy = board;
+                        let flipped = copy.try_place(square).unwrap().flipped;
+                        assert_eq!(flipped, reference.place(square), "{board:?}");
+                    }
+                    Move::Pass => reference.pass(),
+                }
+                assert_eq!(ReferenceBoard::from(&next), reference);
+            }
+            let board = game.positions.last().unwrap();
+            assert_eq!(board.legal_moves(), reference.legal_moves(), "{board:?}");
+            assert!(board.is_finished());
+        }
+    }
+}
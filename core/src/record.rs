@@ -0,0 +1,351 @@
+use std::fmt;
+
+use crate::{GameStatus, Move, MoveError, MoveOutcome, OthelloBoard, Undo};
+
+/// A move in a [`GameRecord`] with the discs it flipped.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RecordedMove {
+    pub mv: Move,
+    /// Opponent discs turned over, `0` for a pass.
+    pub flipped: u64,
+}
+
+impl RecordedMove {
+    /// Plays the move again on `board`, which must be the position it was
+    /// recorded from.
+    #[inline]
+    pub(crate) fn replay(self, board: &mut OthelloBoard) {
+        board
+            .play(self.mv)
+            .expect("recorded moves are legal from the position they were played in");
+    }
+
+    /// The [`Undo`] for this move, played at `ply`.
+    #[inline]
+    fn undo(self, ply: u16) -> Undo {
+        Undo {
+            square: match self.mv {
+                Move::Place(square) => Some(square),
+                Move::Pass => None,
+            },
+            flipped: self.flipped,
+            ply,
+        }
+    }
+}
+
+/// A move list that does not replay from its initial position. `index`
+/// counts moves, starting at zero.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ReplayError {
+    pub index: usize,
+    pub mv: Move,
+    pub reason: MoveError,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "move {} ({}): {}", self.index + 1, self.mv, self.reason)
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// A game from an initial position, with every move and pass played and a
+/// cursor into them for takebacks and replays.
+///
+/// Moves after the cursor are kept for [`GameRecord::redo`] until a
+/// different move is played there.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GameRecord {
+    initial: OthelloBoard,
+    moves: Vec<RecordedMove>,
+    cursor: usize,
+    /// Position at the cursor.
+    board: OthelloBoard,
+}
+
+impl Default for GameRecord {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GameRecord {
+    /// An empty record from the standard starting position.
+    pub const fn new() -> Self {
+        Self::from_position(OthelloBoard::new())
+    }
+
+    pub const fn from_position(initial: OthelloBoard) -> Self {
+        GameRecord {
+            initial,
+            moves: Vec::new(),
+            cursor: 0,
+            board: initial,
+        }
+    }
+
+    /// Replays `moves` from `initial`, leaving the cursor at the end. Unlike
+    /// [`OthelloBoard::play_moves`], passes must be listed explicitly.
+    pub fn from_moves(
+        initial: OthelloBoard,
+        moves: impl IntoIterator<Item = Move>,
+    ) -> Result<Self, ReplayError> {
+        let mut record = Self::from_position(initial);
+        for (index, mv) in moves.into_iter().enumerate() {
+            record
+                .play(mv)
+                .map_err(|reason| ReplayError { index, mv, reason })?;
+        }
+        Ok(record)
+    }
+
+    #[inline]
+    pub const fn initial(&self) -> &OthelloBoard {
+        &self.initial
+    }
+
+    /// Every recorded move, including those after the cursor.
+    #[inline]
+    pub fn moves(&self) -> &[RecordedMove] {
+        &self.moves
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Number of moves played to reach the current position.
+    #[inline]
+    pub const fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Position at the cursor.
+    #[inline]
+    pub const fn board(&self) -> &OthelloBoard {
+        &self.board
+    }
+
+    /// Rebuilds the position after `index` moves from the initial board,
+    /// without moving the cursor.
+    pub fn board_at(&self, index: usize) -> Option<OthelloBoard> {
+        let mut board = self.initial;
+        for recorded in self.moves.get(..index)? {
+            recorded.replay(&mut board);
+        }
+        Some(board)
+    }
+
+    /// Plays `mv` at the cursor. Moves after the cursor are dropped unless
+    /// `mv` is the next one, in which case this is a [`GameRecord::redo`].
+    pub fn play(&mut self, mv: Move) -> Result<Option<MoveOutcome>, MoveError> {
+        let outcome = self.board.play(mv)?;
+        let recorded = RecordedMove {
+            mv,
+            flipped: outcome.map_or(0, |outcome| outcome.flipped),
+        };
+        if self.moves.get(self.cursor) != Some(&recorded) {
+            self.moves.truncate(self.cursor);
+            self.moves.push(recorded);
+        }
+        self.cursor += 1;
+        Ok(outcome)
+    }
+
+    /// Plays a pass if the side to move has no legal move, so a caller can
+    /// follow every placement with it. Returns whether it passed.
+    pub fn pass_if_needed(&mut self) -> bool {
+        self.board.status() == GameStatus::MustPass && self.play(Move::Pass).is_ok()
+    }
+
+    /// Steps back one move, returning it.
+    pub fn undo(&mut self) -> Option<Move> {
+        let index = self.cursor.checked_sub(1)?;
+        let recorded = *self.moves.get(index)?;
+        let ply =
+            u16::try_from(index).map_or(u16::MAX, |index| self.initial.ply.saturating_add(index));
+        self.board.unmake_move(recorded.undo(ply));
+        self.cursor -= 1;
+        Some(recorded.mv)
+    }
+
+    /// Steps forward one move, returning it.
+    pub fn redo(&mut self) -> Option<Move> {
+        let recorded = *self.moves.get(self.cursor)?;
+        recorded.replay(&mut self.board);
+        self.cursor += 1;
+        Some(recorded.mv)
+    }
+
+    /// Moves the cursor to `index` moves from the start. Returns `false`,
+    /// leaving the cursor alone, when `index` is past the last move.
+    pub fn jump(&mut self, index: usize) -> bool {
+        if index > self.moves.len() {
+            return false;
+        }
+        while self.cursor > index {
+            self.undo();
+        }
+        while self.cursor < index {
+            self.redo();
+        }
+        true
+    }
+}
+
+/// Wire representation used by the serde implementations of [`GameRecord`].
+/// Flipped masks are recomputed, so a record is replayed on the way in.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct RecordRepr {
+    initial: OthelloBoard,
+    moves: Vec<Move>,
+    cursor: usize,
+}
+
+impl serde::Serialize for GameRecord {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RecordRepr {
+            initial: self.initial,
+            moves: self.moves.iter().map(|recorded| recorded.mv).collect(),
+            cursor: self.cursor,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for GameRecord {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let repr = RecordRepr::deserialize(deserializer)?;
+        let mut record =
+            GameRecord::from_moves(repr.initial, repr.moves).map_err(serde::de::Error::custom)?;
+        if !record.jump(repr.cursor) {
+            return Err(serde::de::Error::custom("cursor is past the last move"));
+        }
+        Ok(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OthelloColor, Square};
+
+    fn record(transcript: &str) -> GameRecord {
+        let moves = crate::parse_transcript(transcript).unwrap();
+        GameRecord::from_moves(OthelloBoard::new(), moves.into_iter().map(Move::Place)).unwrap()
+    }
+
+    #[test]
+    fn test_undo_redo_and_jump() {
+        let mut game = record("f5d6c3d3c4");
+        assert_eq!(game.cursor(), 5);
+        assert_eq!(
+            *game.board(),
+            OthelloBoard::from_transcript("f5d6c3d3c4").unwrap()
+        );
+
+        assert_eq!(game.undo(), Some(Move::Place(Square::C4)));
+        assert_eq!(game.undo(), Some(Move::Place(Square::D3)));
+        assert_eq!(
+            *game.board(),
+            OthelloBoard::from_transcript("f5d6c3").unwrap()
+        );
+        assert_eq!(game.redo(), Some(Move::Place(Square::D3)));
+        assert_eq!(game.cursor(), 4);
+        assert_eq!(game.len(), 5);
+
+        assert!(game.jump(0));
+        assert_eq!(*game.board(), OthelloBoard::new());
+        assert_eq!(game.undo(), None);
+        assert!(game.jump(5));
+        assert_eq!(game.redo(), None);
+        assert!(!game.jump(6));
+        assert_eq!(game.cursor(), 5);
+
+        for index in 0..=5 {
+            assert!(game.jump(index));
+            assert_eq!(game.board_at(index), Some(*game.board()));
+        }
+        assert_eq!(game.board_at(6), None);
+    }
+
+    #[test]
+    fn test_undo_near_max_ply() {
+        let mut initial = OthelloBoard::new();
+        initial.ply = u16::MAX - 1;
+        let moves = [Square::F5, Square::D6, Square::C3].map(Move::Place);
+        let mut game = GameRecord::from_moves(initial, moves).unwrap();
+        assert_eq!(game.board().ply, u16::MAX);
+        for index in (0..3).rev() {
+            game.undo();
+            assert_eq!(game.board_at(index), Some(*game.board()));
+        }
+        assert_eq!(*game.board(), initial);
+    }
+
+    #[test]
+    fn test_play_after_undo() {
+        let mut game = record("f5d6c3");
+        game.undo();
+        game.undo();
+        // Replaying the recorded move keeps the rest for redo.
+        game.play(Move::Place(Square::D6)).unwrap();
+        assert_eq!(game.len(), 3);
+        // A different move replaces it.
+        game.undo();
+        game.play(Move::Place(Square::F6)).unwrap();
+        assert_eq!(game.len(), 2);
+        assert_eq!(game.redo(), None);
+        assert_eq!(game.play(Move::Place(Square::A1)), Err(MoveError::NoFlips));
+        assert_eq!(game.len(), 2);
+    }
+
+    #[test]
+    fn test_passes() {
+        let board = OthelloBoard::from_bitboards(
+            Square::B1.mask() | Square::C1.mask(),
+            Square::A1.mask() | Square::H8.mask() | Square::G8.mask(),
+            OthelloColor::Black,
+        );
+        let mut game = GameRecord::from_position(board);
+        assert_eq!(game.play(Move::Pass), Ok(None));
+        assert!(!game.pass_if_needed());
+        game.play(Move::Place(Square::D1)).unwrap();
+        game.jump(0);
+        assert_eq!(*game.board(), board);
+        game.jump(2);
+        assert_eq!(game.board_at(2), Some(*game.board()));
+        assert_eq!(
+            game.moves()[1],
+            RecordedMove {
+                mv: Move::Place(Square::D1),
+                flipped: Square::B1.mask() | Square::C1.mask(),
+            }
+        );
+
+        let error = GameRecord::from_moves(OthelloBoard::new(), [Move::Pass]).unwrap_err();
+        assert_eq!(error.reason, MoveError::IllegalPass);
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let mut game = record("f5d6c3d3c4f4");
+        game.jump(2);
+        let json = serde_json::to_string(&game).unwrap();
+        assert_eq!(serde_json::from_str::<GameRecord>(&json).unwrap(), game);
+
+        let json = json.replace("\"cursor\":2", "\"cursor\":7");
+        assert!(serde_json::from_str::<GameRecord>(&json).is_err());
+    }
+}
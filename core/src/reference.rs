@@ -0,0 +1,96 @@
+//! A deliberately naive board used to cross-check the bitboard code. It
+//! walks each direction square by square on a plain array.
+
+use crate::{OthelloBoard, OthelloColor, Square};
+
+const DIRECTIONS: [(i8, i8); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ReferenceBoard {
+    cells: [[Option<OthelloColor>; 8]; 8],
+    turn: OthelloColor,
+}
+
+impl From<&OthelloBoard> for ReferenceBoard {
+    fn from(board: &OthelloBoard) -> Self {
+        let mut cells = [[None; 8]; 8];
+        for square in Square::all() {
+            let cell = &mut cells[square.rank() as usize][square.file() as usize];
+            if board.black & square.mask() != 0 {
+                *cell = Some(OthelloColor::Black);
+            } else if board.white & square.mask() != 0 {
+                *cell = Some(OthelloColor::White);
+            }
+        }
+        ReferenceBoard {
+            cells,
+            turn: board.get_turn(),
+        }
+    }
+}
+
+impl ReferenceBoard {
+    fn get(&self, x: i8, y: i8) -> Option<Option<OthelloColor>> {
+        if (0..8).contains(&x) && (0..8).contains(&y) {
+            Some(self.cells[y as usize][x as usize])
+        } else {
+            None
+        }
+    }
+
+    /// Squares that placing on `square` would flip.
+    fn flips(&self, square: Square) -> Vec<(i8, i8)> {
+        let (x, y) = (square.file() as i8, square.rank() as i8);
+        let mut flips = Vec::new();
+        if self.get(x, y) != Some(None) {
+            return flips;
+        }
+        for (dx, dy) in DIRECTIONS {
+            let mut line = Vec::new();
+            let (mut cx, mut cy) = (x + dx, y + dy);
+            while self.get(cx, cy) == Some(Some(self.turn.opponent())) {
+                line.push((cx, cy));
+                cx += dx;
+                cy += dy;
+            }
+            if !line.is_empty() && self.get(cx, cy) == Some(Some(self.turn)) {
+                flips.extend(line);
+            }
+        }
+        flips
+    }
+
+    pub fn legal_moves(&self) -> u64 {
+        Square::all()
+            .filter(|&square| !self.flips(square).is_empty())
+            .fold(0, |mask, square| mask | square.mask())
+    }
+
+    /// Plays `square` and returns the flipped mask.
+    pub fn place(&mut self, square: Square) -> u64 {
+        let flips = self.flips(square);
+        let mut mask = 0;
+        for &(x, y) in &flips {
+            self.cells[y as usize][x as usize] = Some(self.turn);
+            mask |= 1 << (y * 8 + x);
+        }
+        if !flips.is_empty() {
+            self.cells[square.rank() as usize][square.file() as usize] = Some(self.turn);
+            self.turn = self.turn.opponent();
+        }
+        mask
+    }
+
+    pub fn pass(&mut self) {
+        self.turn = self.turn.opponent();
+    }
+}
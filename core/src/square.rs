@@ -0,0 +1,189 @@
+/// A single square on the board, stored as its bit index (`y * 8 + x`,
+/// so a1 is bit 0 and h8 is bit 63).
+#[rustfmt::skip]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Ord, PartialOrd, Hash)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub struct Square(u8);
+
+macro_rules! square_consts {
+    ($($name:ident = $index:literal),* $(,)?) => {
+        impl Square {
+            $(pub const $name: Square = Square($index);)*
+        }
+    };
+}
+
+square_consts!(
+    A1 = 0,
+    B1 = 1,
+    C1 = 2,
+    D1 = 3,
+    E1 = 4,
+    F1 = 5,
+    G1 = 6,
+    H1 = 7,
+    A2 = 8,
+    B2 = 9,
+    C2 = 10,
+    D2 = 11,
+    E2 = 12,
+    F2 = 13,
+    G2 = 14,
+    H2 = 15,
+    A3 = 16,
+    B3 = 17,
+    C3 = 18,
+    D3 = 19,
+    E3 = 20,
+    F3 = 21,
+    G3 = 22,
+    H3 = 23,
+    A4 = 24,
+    B4 = 25,
+    C4 = 26,
+    D4 = 27,
+    E4 = 28,
+    F4 = 29,
+    G4 = 30,
+    H4 = 31,
+    A5 = 32,
+    B5 = 33,
+    C5 = 34,
+    D5 = 35,
+    E5 = 36,
+    F5 = 37,
+    G5 = 38,
+    H5 = 39,
+    A6 = 40,
+    B6 = 41,
+    C6 = 42,
+    D6 = 43,
+    E6 = 44,
+    F6 = 45,
+    G6 = 46,
+    H6 = 47,
+    A7 = 48,
+    B7 = 49,
+    C7 = 50,
+    D7 = 51,
+    E7 = 52,
+    F7 = 53,
+    G7 = 54,
+    H7 = 55,
+    A8 = 56,
+    B8 = 57,
+    C8 = 58,
+    D8 = 59,
+    E8 = 60,
+    F8 = 61,
+    G8 = 62,
+    H8 = 63,
+);
+
+impl Square {
+    #[inline]
+    pub const fn from_index(index: u8) -> Option<Self> {
+        if index < 64 {
+            Some(Square(index))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub const fn from_coords(x: u8, y: u8) -> Option<Self> {
+        if x < 8 && y < 8 {
+            Some(Square(y * 8 + x))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the square of a mask with exactly one bit set.
+    #[inline]
+    pub const fn from_mask(mask: u64) -> Option<Self> {
+        if mask.count_ones() == 1 {
+            Some(Square(mask.trailing_zeros() as u8))
+        } else {
+            None
+        }
+    }
+
+    /// Iterates over all squares from a1 to h8.
+    pub fn all() -> impl DoubleEndedIterator<Item = Square> + ExactSizeIterator {
+        (0..64).map(Square)
+    }
+
+    #[inline]
+    pub const fn index(self) -> u8 {
+        self.0
+    }
+
+    /// Column from `0` (a-file) to `7` (h-file).
+    #[inline]
+    pub const fn file(self) -> u8 {
+        self.0 % 8
+    }
+
+    /// Row from `0` (rank 1) to `7` (rank 8).
+    #[inline]
+    pub const fn rank(self) -> u8 {
+        self.0 / 8
+    }
+
+    #[inline]
+    pub const fn mask(self) -> u64 {
+        1 << self.0
+    }
+}
+
+impl TryFrom<u8> for Square {
+    type Error = &'static str;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Square::from_index(value).ok_or("square index out of range")
+    }
+}
+
+impl From<Square> for u8 {
+    fn from(value: Square) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constants_match_coords() {
+        assert_eq!(Square::A1.index(), 0);
+        assert_eq!(Square::H1.index(), 7);
+        assert_eq!(Square::A8.index(), 56);
+        assert_eq!(Square::H8.index(), 63);
+        assert_eq!(Square::from_coords(2, 3), Some(Square::C4));
+        assert_eq!(Square::F5.file(), 5);
+        assert_eq!(Square::F5.rank(), 4);
+    }
+
+    #[test]
+    fn test_from_mask() {
+        assert_eq!(Square::from_mask(1 << 37), Some(Square::F5));
+        assert_eq!(Square::from_mask(0), None);
+        assert_eq!(Square::from_mask(0b11), None);
+        assert_eq!(Square::from_index(64), None);
+        assert_eq!(Square::from_coords(8, 0), None);
+    }
+
+    #[test]
+    fn test_all() {
+        assert_eq!(Square::all().len(), 64);
+        assert!(
+            Square::all()
+                .enumerate()
+                .all(|(i, sq)| sq.index() as usize == i
+                    && Square::from_coords(sq.file(), sq.rank()) == Some(sq))
+        );
+    }
+}
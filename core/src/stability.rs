@@ -0,0 +1,147 @@
+use crate::{
+    OthelloBoard, OthelloColor,
+    backend::shift::{FILE_A, FILE_H},
+    line,
+};
+
+const RANK_1: u64 = 0x0000_0000_0000_00ff;
+const RANK_8: u64 = 0xff00_0000_0000_0000;
+const BORDER: u64 = FILE_A | FILE_H | RANK_1 | RANK_8;
+
+/// `(shift, <<-mask, >>-mask, edge)` for each line direction. The masks drop
+/// squares that a shift would wrap onto the other side of the board; `edge`
+/// holds the squares with a neighbour off the board along that direction.
+const DIRECTIONS: [(u32, u64, u64, u64); 4] = [
+    (1, !FILE_A, !FILE_H, FILE_A | FILE_H),
+    (8, u64::MAX, u64::MAX, RANK_1 | RANK_8),
+    (7, !FILE_H, !FILE_A, BORDER),
+    (9, !FILE_A, !FILE_H, BORDER),
+];
+
+/// Spreads `from` along both ways of a line direction to the board edge.
+#[inline]
+fn spread(from: u64, shift: u32, up: u64, down: u64) -> u64 {
+    let mut up_line = line!(up, from, <<, shift);
+    up_line |= up & (up_line << shift);
+    let mut down_line = line!(down, from, >>, shift);
+    down_line |= down & (down_line >> shift);
+    from | up_line | down_line
+}
+
+impl OthelloBoard {
+    /// Discs of `color` that can never be flipped for the rest of the game.
+    ///
+    /// A disc is stable when, along each of the four line directions, the
+    /// line through it is full, or it touches the edge, or it touches a
+    /// stable disc of its own color. The result is conservative: it can miss
+    /// stable discs in unusual positions but never includes a flippable one.
+    pub fn stable_discs(&self, color: OthelloColor) -> u64 {
+        let own = match color {
+            OthelloColor::Black => self.black,
+            OthelloColor::White => self.white,
+        };
+        let empty = !(self.black | self.white);
+        let full = DIRECTIONS.map(|(shift, up, down, _)| !spread(empty, shift, up, down));
+
+        let mut stable = 0;
+        loop {
+            let mut next = own;
+            for ((shift, up, down, edge), full) in DIRECTIONS.into_iter().zip(full) {
+                next &= full | edge | ((stable << shift) & up) | ((stable >> shift) & down);
+            }
+            if next == stable {
+                return stable;
+            }
+            stable = next;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Bitboard, Square, Symmetry, testing::random_games};
+
+    fn mask(squares: &[Square]) -> u64 {
+        squares.iter().copied().collect::<Bitboard>().bits()
+    }
+
+    #[test]
+    fn test_initial_position_has_no_stable_discs() {
+        let board = OthelloBoard::new();
+        assert_eq!(board.stable_discs(OthelloColor::Black), 0);
+        assert_eq!(board.stable_discs(OthelloColor::White), 0);
+    }
+
+    #[test]
+    fn test_corner_anchored() {
+        // a1 b1 c1 a2 b2 are all anchored to the corner; e1 is not.
+        let black = mask(&[Square::A1, Square::B1, Square::C1, Square::A2, Square::B2]);
+        let board = OthelloBoard::from_bitboards(
+            black | Square::E1.mask(),
+            mask(&[Square::D4, Square::F1]),
+            OthelloColor::Black,
+        );
+        assert_eq!(board.stable_discs(OthelloColor::Black), black);
+        assert_eq!(board.stable_discs(OthelloColor::White), 0);
+    }
+
+    #[test]
+    fn test_full_edge() {
+        let black = mask(&[Square::A1, Square::B1, Square::C1, Square::D1]);
+        let white = mask(&[Square::E1, Square::F1, Square::G1, Square::H1]);
+        let board = OthelloBoard::from_bitboards(
+            black | Square::D4.mask(),
+            white | Square::E4.mask(),
+            OthelloColor::Black,
+        );
+        assert_eq!(board.stable_discs(OthelloColor::Black), black);
+        assert_eq!(board.stable_discs(OthelloColor::White), white);
+    }
+
+    #[test]
+    fn test_full_lines() {
+        // Only h8 is empty. White can still play there and flip g7, f6 and
+        // e5 back to d4; every other black disc is stable.
+        let white = Square::D4.mask();
+        let black = !(white | Square::H8.mask());
+        let board = OthelloBoard::from_bitboards(black, white, OthelloColor::White);
+        assert_eq!(
+            board.stable_discs(OthelloColor::Black),
+            black & !mask(&[Square::E5, Square::F6, Square::G7])
+        );
+        assert_eq!(board.stable_discs(OthelloColor::White), 0);
+
+        let full =
+            OthelloBoard::from_bitboards(black | Square::H8.mask(), white, OthelloColor::Black);
+        assert_eq!(full.stable_discs(OthelloColor::White), white);
+    }
+
+    /// Stable discs keep their color for the rest of the game, and the result
+    /// does not depend on the board orientation.
+    #[test]
+    fn test_random_games() {
+        for game in random_games(2).take(200) {
+            let mut black = 0;
+            let mut white = 0;
+            for board in &game.positions {
+                assert_eq!(board.black & black, black, "{board:?}");
+                assert_eq!(board.white & white, white, "{board:?}");
+                let stable = board.stable_discs(OthelloColor::Black);
+                for symmetry in Symmetry::ALL {
+                    assert_eq!(
+                        board.transform(symmetry).stable_discs(OthelloColor::Black),
+                        symmetry.apply_mask(stable)
+                    );
+                }
+                black |= stable;
+                white |= board.stable_discs(OthelloColor::White);
+            }
+            let board = game.positions.last().unwrap();
+            // Everything is stable once the game is over on a full board.
+            if board.black | board.white == u64::MAX {
+                assert_eq!(board.stable_discs(OthelloColor::Black), board.black);
+            }
+        }
+    }
+}
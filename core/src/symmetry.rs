@@ -0,0 +1,228 @@
+use crate::{Bitboard, OthelloBoard, Square};
+
+/// One of the eight symmetries of the square board. Rotations are
+/// clockwise with rank 8 at the top.
+#[rustfmt::skip]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Ord, PartialOrd, Hash)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirrors ranks, swapping rank 1 and rank 8.
+    FlipVertical,
+    /// Mirrors files, swapping the a-file and the h-file.
+    FlipHorizontal,
+    /// Mirrors along the a1-h8 diagonal.
+    FlipDiagonal,
+    /// Mirrors along the h1-a8 diagonal.
+    FlipAntiDiagonal,
+}
+
+#[inline]
+const fn flip_vertical(x: u64) -> u64 {
+    x.swap_bytes()
+}
+
+#[inline]
+const fn flip_horizontal(mut x: u64) -> u64 {
+    const K1: u64 = 0x5555555555555555;
+    const K2: u64 = 0x3333333333333333;
+    const K4: u64 = 0x0f0f0f0f0f0f0f0f;
+    x = ((x >> 1) & K1) | ((x & K1) << 1);
+    x = ((x >> 2) & K2) | ((x & K2) << 2);
+    ((x >> 4) & K4) | ((x & K4) << 4)
+}
+
+#[inline]
+const fn flip_diagonal(mut x: u64) -> u64 {
+    const K1: u64 = 0x5500550055005500;
+    const K2: u64 = 0x3333000033330000;
+    const K4: u64 = 0x0f0f0f0f00000000;
+    let mut t = K4 & (x ^ (x << 28));
+    x ^= t ^ (t >> 28);
+    t = K2 & (x ^ (x << 14));
+    x ^= t ^ (t >> 14);
+    t = K1 & (x ^ (x << 7));
+    x ^ t ^ (t >> 7)
+}
+
+#[inline]
+const fn flip_anti_diagonal(mut x: u64) -> u64 {
+    const K1: u64 = 0xaa00aa00aa00aa00;
+    const K2: u64 = 0xcccc0000cccc0000;
+    const K4: u64 = 0xf0f0f0f00f0f0f0f;
+    let mut t = x ^ (x << 36);
+    x ^= K4 & (t ^ (x >> 36));
+    t = K2 & (x ^ (x << 18));
+    x ^= t ^ (t >> 18);
+    t = K1 & (x ^ (x << 9));
+    x ^ t ^ (t >> 9)
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::FlipVertical,
+        Symmetry::FlipHorizontal,
+        Symmetry::FlipDiagonal,
+        Symmetry::FlipAntiDiagonal,
+    ];
+
+    /// The symmetry that undoes `self`.
+    #[inline]
+    pub const fn inverse(self) -> Self {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            other => other,
+        }
+    }
+
+    #[inline]
+    pub const fn apply_mask(self, mask: u64) -> u64 {
+        match self {
+            Symmetry::Identity => mask,
+            Symmetry::Rotate90 => flip_vertical(flip_diagonal(mask)),
+            Symmetry::Rotate180 => flip_horizontal(flip_vertical(mask)),
+            Symmetry::Rotate270 => flip_horizontal(flip_diagonal(mask)),
+            Symmetry::FlipVertical => flip_vertical(mask),
+            Symmetry::FlipHorizontal => flip_horizontal(mask),
+            Symmetry::FlipDiagonal => flip_diagonal(mask),
+            Symmetry::FlipAntiDiagonal => flip_anti_diagonal(mask),
+        }
+    }
+
+    #[inline]
+    pub const fn apply_square(self, square: Square) -> Square {
+        let (f, r) = (square.file(), square.rank());
+        let (x, y) = match self {
+            Symmetry::Identity => (f, r),
+            Symmetry::Rotate90 => (r, 7 - f),
+            Symmetry::Rotate180 => (7 - f, 7 - r),
+            Symmetry::Rotate270 => (7 - r, f),
+            Symmetry::FlipVertical => (f, 7 - r),
+            Symmetry::FlipHorizontal => (7 - f, r),
+            Symmetry::FlipDiagonal => (r, f),
+            Symmetry::FlipAntiDiagonal => (7 - r, 7 - f),
+        };
+        match Square::from_coords(x, y) {
+            Some(square) => square,
+            None => unreachable!(),
+        }
+    }
+}
+
+impl Square {
+    #[inline]
+    pub const fn transform(self, symmetry: Symmetry) -> Square {
+        symmetry.apply_square(self)
+    }
+}
+
+impl Bitboard {
+    #[inline]
+    pub const fn transform(self, symmetry: Symmetry) -> Bitboard {
+        Bitboard(symmetry.apply_mask(self.0))
+    }
+}
+
+impl OthelloBoard {
+    /// Applies `symmetry` to the discs. Side to move and ply are kept.
+    #[inline]
+    pub const fn transform(&self, symmetry: Symmetry) -> OthelloBoard {
+        OthelloBoard {
+            black: symmetry.apply_mask(self.black),
+            white: symmetry.apply_mask(self.white),
+            turn: self.turn,
+            ply: self.ply,
+        }
+    }
+
+    /// Returns the representative of this position's symmetry class with the
+    /// smallest `(black, white)` pair, and the symmetry that maps `self` to
+    /// it. Map moves back with [`Symmetry::inverse`].
+    pub fn canonical(&self) -> (OthelloBoard, Symmetry) {
+        let mut best = (*self, Symmetry::Identity);
+        for symmetry in &Symmetry::ALL[1..] {
+            let candidate = self.transform(*symmetry);
+            if (candidate.black, candidate.white) < (best.0.black, best.0.white) {
+                best = (candidate, *symmetry);
+            }
+        }
+        best
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_matches_square_mapping() {
+        for symmetry in Symmetry::ALL {
+            for square in Square::all() {
+                assert_eq!(
+                    symmetry.apply_mask(square.mask()),
+                    square.transform(symmetry).mask(),
+                    "{symmetry:?} {square}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_square_mapping() {
+        assert_eq!(Square::A1.transform(Symmetry::Rotate90), Square::A8);
+        assert_eq!(Square::A8.transform(Symmetry::Rotate90), Square::H8);
+        assert_eq!(Square::A1.transform(Symmetry::Rotate270), Square::H1);
+        assert_eq!(Square::B1.transform(Symmetry::FlipDiagonal), Square::A2);
+        assert_eq!(Square::B1.transform(Symmetry::FlipAntiDiagonal), Square::H7);
+        assert_eq!(Square::C4.transform(Symmetry::FlipVertical), Square::C5);
+        assert_eq!(Square::C4.transform(Symmetry::FlipHorizontal), Square::F4);
+        assert_eq!(Square::C4.transform(Symmetry::Rotate180), Square::F5);
+    }
+
+    #[test]
+    fn test_inverse() {
+        for symmetry in Symmetry::ALL {
+            for square in Square::all() {
+                assert_eq!(
+                    square.transform(symmetry).transform(symmetry.inverse()),
+                    square
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_transform_preserves_legality() {
+        let board = OthelloBoard::from_transcript("f5d6c3d3c4").unwrap();
+        for symmetry in Symmetry::ALL {
+            let transformed = board.transform(symmetry);
+            assert_eq!(
+                transformed.legal_moves(),
+                symmetry.apply_mask(board.legal_moves())
+            );
+        }
+    }
+
+    #[test]
+    fn test_canonical() {
+        // The four opening moves are symmetric to each other.
+        let openings = ["f5", "d3", "c4", "e6"].map(|m| OthelloBoard::from_transcript(m).unwrap());
+        let canonical = openings[0].canonical().0;
+        for board in openings {
+            let (c, symmetry) = board.canonical();
+            assert_eq!(c, canonical);
+            assert_eq!(board.transform(symmetry), c);
+            assert_eq!(c.transform(symmetry.inverse()), board);
+        }
+        let other = OthelloBoard::from_transcript("f5f6").unwrap().canonical().0;
+        assert_ne!(other, canonical);
+    }
+}
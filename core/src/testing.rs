@@ -0,0 +1,72 @@
+//! A seeded generator and reproducible random games, shared by the tests,
+//! benches and engine playouts. Only built with the `testing` feature.
+
+use crate::{Move, OthelloBoard};
+
+/// xorshift64*: small, fast and reproducible from a seed on every target,
+/// which is all playouts and move choices need.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // A zero state would only ever produce zeros.
+        Rng((seed ^ 0x9e37_79b9_7f4a_7c15).max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Uniform in `0..n`; `n` must not be zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        (((self.next_u64() >> 32) * n as u64) >> 32) as usize
+    }
+
+    /// `true` with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+}
+
+/// A finished game of uniformly random moves from the initial position.
+#[derive(Debug, Clone)]
+pub struct RandomGame {
+    /// Every position from the start to the end of the game.
+    pub positions: Vec<OthelloBoard>,
+    /// `moves[i]` leads from `positions[i]` to `positions[i + 1]`. Passes
+    /// are included.
+    pub moves: Vec<Move>,
+}
+
+/// An endless, reproducible stream of random games.
+pub fn random_games(seed: u64) -> impl Iterator<Item = RandomGame> {
+    let mut rng = Rng::new(seed);
+    std::iter::repeat_with(move || {
+        let mut board = OthelloBoard::new();
+        let mut game = RandomGame {
+            positions: vec![board],
+            moves: Vec::new(),
+        };
+        loop {
+            let moves: Vec<_> = board.legal_squares().into_iter().collect();
+            let mv = if moves.is_empty() {
+                if !board.pass() {
+                    return game;
+                }
+                Move::Pass
+            } else {
+                let square = moves[rng.below(moves.len())];
+                board
+                    .try_place(square)
+                    .expect("squares come from the legal moves");
+                Move::Place(square)
+            };
+            game.moves.push(mv);
+            game.positions.push(board);
+        }
+    })
+}
@@ -0,0 +1,513 @@
+use std::{fmt, str::FromStr};
+
+use crate::{GameRecord, Move, MoveError, OthelloBoard, ReplayError};
+
+/// Move annotation in the usual chess notation.
+#[rustfmt::skip]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[derive(serde::Serialize, serde::Deserialize)]
+pub enum Annotation {
+    /// `!!`
+    Brilliant,
+    /// `!`
+    Good,
+    /// `!?`
+    Interesting,
+    /// `?!`
+    Dubious,
+    /// `?`
+    Mistake,
+    /// `??`
+    Blunder,
+}
+
+impl Annotation {
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Annotation::Brilliant => "!!",
+            Annotation::Good => "!",
+            Annotation::Interesting => "!?",
+            Annotation::Dubious => "?!",
+            Annotation::Mistake => "?",
+            Annotation::Blunder => "??",
+        }
+    }
+}
+
+impl fmt::Display for Annotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ParseAnnotationError;
+
+impl fmt::Display for ParseAnnotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid annotation")
+    }
+}
+
+impl std::error::Error for ParseAnnotationError {}
+
+impl FromStr for Annotation {
+    type Err = ParseAnnotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "!!" => Annotation::Brilliant,
+            "!" => Annotation::Good,
+            "!?" => Annotation::Interesting,
+            "?!" => Annotation::Dubious,
+            "?" => Annotation::Mistake,
+            "??" => Annotation::Blunder,
+            _ => return Err(ParseAnnotationError),
+        })
+    }
+}
+
+/// Index of a node in its [`GameTree`]. Ids are only meaningful for the
+/// tree that returned them.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Node {
+    board: OthelloBoard,
+    mv: Option<Move>,
+    parent: Option<NodeId>,
+    children: Vec<NodeId>,
+    pub comment: Option<String>,
+    pub annotation: Option<Annotation>,
+    /// Score from the point of view of the side to move at this node, in
+    /// whatever unit the analysis used.
+    pub evaluation: Option<i32>,
+}
+
+impl Node {
+    /// Position after [`Node::mv`].
+    #[inline]
+    pub const fn board(&self) -> &OthelloBoard {
+        &self.board
+    }
+
+    /// Move that led here, `None` at the root.
+    #[inline]
+    pub const fn mv(&self) -> Option<Move> {
+        self.mv
+    }
+
+    #[inline]
+    pub const fn parent(&self) -> Option<NodeId> {
+        self.parent
+    }
+
+    /// Replies to this position. The first one continues the main line,
+    /// the others are variations.
+    #[inline]
+    pub fn children(&self) -> &[NodeId] {
+        &self.children
+    }
+}
+
+/// Moves and variations from a starting position, with comments,
+/// annotations and evaluations on any node.
+///
+/// Methods taking a [`NodeId`] panic when given an id from another tree.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GameTree {
+    nodes: Vec<Node>,
+}
+
+impl Default for GameTree {
+    fn default() -> Self {
+        Self::new(OthelloBoard::new())
+    }
+}
+
+impl GameTree {
+    pub fn new(root: OthelloBoard) -> Self {
+        GameTree {
+            nodes: vec![Node {
+                board: root,
+                mv: None,
+                parent: None,
+                children: Vec::new(),
+                comment: None,
+                annotation: None,
+                evaluation: None,
+            }],
+        }
+    }
+
+    #[inline]
+    pub const fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    #[inline]
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id.0]
+    }
+
+    #[inline]
+    pub fn node_mut(&mut self, id: NodeId) -> &mut Node {
+        &mut self.nodes[id.0]
+    }
+
+    /// Number of nodes, counting the root.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Always `false`: a tree has at least its root.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Plays `mv` from `parent`. Returns the existing child if `mv` was
+    /// already played there; otherwise adds it as the last variation, or as
+    /// the main line if `parent` had no replies.
+    pub fn add_move(&mut self, parent: NodeId, mv: Move) -> Result<NodeId, MoveError> {
+        if let Some(&child) = self
+            .node(parent)
+            .children
+            .iter()
+            .find(|&&child| self.node(child).mv == Some(mv))
+        {
+            return Ok(child);
+        }
+        let mut board = self.node(parent).board;
+        board.play(mv)?;
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(Node {
+            board,
+            mv: Some(mv),
+            parent: Some(parent),
+            children: Vec::new(),
+            comment: None,
+            annotation: None,
+            evaluation: None,
+        });
+        self.node_mut(parent).children.push(id);
+        Ok(id)
+    }
+
+    /// Plays `moves` one after another from `parent`, see
+    /// [`GameTree::add_move`], and returns the last node. Moves before an
+    /// illegal one stay in the tree.
+    pub fn add_variation(
+        &mut self,
+        parent: NodeId,
+        moves: impl IntoIterator<Item = Move>,
+    ) -> Result<NodeId, ReplayError> {
+        let mut id = parent;
+        for (index, mv) in moves.into_iter().enumerate() {
+            id = self
+                .add_move(id, mv)
+                .map_err(|reason| ReplayError { index, mv, reason })?;
+        }
+        Ok(id)
+    }
+
+    /// Makes the line through `id` the main line, by moving it and each of
+    /// its ancestors to the front of their siblings.
+    pub fn promote(&mut self, id: NodeId) {
+        let mut child = id;
+        while let Some(parent) = self.node(child).parent {
+            let children = &mut self.node_mut(parent).children;
+            if let Some(index) = children.iter().position(|&c| c == child) {
+                children[..=index].rotate_right(1);
+            }
+            child = parent;
+        }
+    }
+
+    /// Nodes from the root to `id`, both included.
+    pub fn path(&self, id: NodeId) -> Vec<NodeId> {
+        let mut path = vec![id];
+        let mut current = id;
+        while let Some(parent) = self.node(current).parent {
+            path.push(parent);
+            current = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// The root followed by the first reply at every node.
+    pub fn main_line(&self) -> Vec<NodeId> {
+        let mut line = vec![self.root()];
+        let mut current = self.root();
+        while let Some(&child) = self.node(current).children.first() {
+            line.push(child);
+            current = child;
+        }
+        line
+    }
+
+    /// The moves from the root to `id` as a [`GameRecord`], with the
+    /// cursor at `id`.
+    pub fn record(&self, id: NodeId) -> GameRecord {
+        let moves = self
+            .path(id)
+            .into_iter()
+            .filter_map(|node| self.node(node).mv);
+        GameRecord::from_moves(self.node(self.root()).board, moves)
+            .expect("tree moves are legal from their parent's position")
+    }
+
+    /// Visits every node depth-first from the root, main line before
+    /// variations, with its depth below the root.
+    pub fn depth_first(&self) -> DepthFirst<'_> {
+        DepthFirst {
+            tree: self,
+            stack: vec![(self.root(), 0)],
+        }
+    }
+}
+
+pub struct DepthFirst<'a> {
+    tree: &'a GameTree,
+    stack: Vec<(NodeId, usize)>,
+}
+
+impl Iterator for DepthFirst<'_> {
+    type Item = (NodeId, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (id, depth) = self.stack.pop()?;
+        let children = &self.tree.node(id).children;
+        self.stack
+            .extend(children.iter().rev().map(|&child| (child, depth + 1)));
+        Some((id, depth))
+    }
+}
+
+/// Wire representation used by the serde implementations of [`GameTree`]:
+/// the nodes in depth-first order, each pointing at an earlier parent, so
+/// long games do not nest. Positions are replayed on the way in.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct TreeRepr {
+    root: OthelloBoard,
+    nodes: Vec<NodeRepr>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct NodeRepr {
+    /// Index into [`TreeRepr::nodes`], `None` for the root.
+    parent: Option<usize>,
+    mv: Option<Move>,
+    comment: Option<String>,
+    annotation: Option<Annotation>,
+    evaluation: Option<i32>,
+}
+
+impl NodeRepr {
+    fn apply(self, node: &mut Node) {
+        node.comment = self.comment;
+        node.annotation = self.annotation;
+        node.evaluation = self.evaluation;
+    }
+}
+
+impl serde::Serialize for GameTree {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let order: Vec<NodeId> = self.depth_first().map(|(id, _)| id).collect();
+        let mut position = vec![0; self.nodes.len()];
+        for (index, id) in order.iter().enumerate() {
+            position[id.0] = index;
+        }
+        TreeRepr {
+            root: self.node(self.root()).board,
+            nodes: order
+                .iter()
+                .map(|&id| {
+                    let node = self.node(id);
+                    NodeRepr {
+                        parent: node.parent.map(|parent| position[parent.0]),
+                        mv: node.mv,
+                        comment: node.comment.clone(),
+                        annotation: node.annotation,
+                        evaluation: node.evaluation,
+                    }
+                })
+                .collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for GameTree {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let repr = TreeRepr::deserialize(deserializer)?;
+        let mut nodes = repr.nodes.into_iter();
+        let root = nodes
+            .next()
+            .ok_or_else(|| D::Error::custom("missing root node"))?;
+        if root.parent.is_some() || root.mv.is_some() {
+            return Err(D::Error::custom("root node has a parent or move"));
+        }
+
+        let mut tree = GameTree::new(repr.root);
+        root.apply(tree.node_mut(tree.root()));
+        let mut ids = vec![tree.root()];
+        for node in nodes {
+            let (Some(parent), Some(mv)) = (node.parent, node.mv) else {
+                return Err(D::Error::custom("node without a parent or move"));
+            };
+            let parent = *ids
+                .get(parent)
+                .ok_or_else(|| D::Error::custom("parent must come before its children"))?;
+            let before = tree.len();
+            let id = tree.add_move(parent, mv).map_err(D::Error::custom)?;
+            if tree.len() == before {
+                return Err(D::Error::custom("duplicate move in a node's children"));
+            }
+            node.apply(tree.node_mut(id));
+            ids.push(id);
+        }
+        Ok(tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Square;
+
+    fn moves(transcript: &str) -> Vec<Move> {
+        crate::parse_transcript(transcript)
+            .unwrap()
+            .into_iter()
+            .map(Move::Place)
+            .collect()
+    }
+
+    /// Main line f5 d6 c3, with variations f5 f6 and f5 d6 c5.
+    fn sample() -> (GameTree, NodeId, NodeId) {
+        let mut tree = GameTree::default();
+        tree.add_variation(tree.root(), moves("f5d6c3")).unwrap();
+        let f5 = tree.node(tree.root()).children()[0];
+        let f6 = tree.add_move(f5, Move::Place(Square::F6)).unwrap();
+        let c5 = tree.add_variation(f5, moves("d6c5")).unwrap();
+        (tree, f6, c5)
+    }
+
+    #[test]
+    fn test_variations() {
+        let (tree, f6, c5) = sample();
+        assert_eq!(tree.len(), 6);
+        let main: Vec<_> = tree
+            .main_line()
+            .into_iter()
+            .filter_map(|id| tree.node(id).mv())
+            .collect();
+        assert_eq!(main, moves("f5d6c3"));
+        assert_eq!(
+            *tree.node(c5).board(),
+            OthelloBoard::from_transcript("f5d6c5").unwrap()
+        );
+        assert_eq!(tree.path(f6).len(), 3);
+        assert_eq!(tree.node(f6).parent(), Some(tree.path(c5)[1]));
+
+        // Replaying an existing move reuses its node.
+        let mut again = tree.clone();
+        again.add_variation(again.root(), moves("f5d6c5")).unwrap();
+        assert_eq!(again, tree);
+
+        let mut tree = tree;
+        let error = tree.add_variation(tree.root(), moves("f5a1")).unwrap_err();
+        assert_eq!((error.index, error.reason), (1, MoveError::NoFlips));
+    }
+
+    #[test]
+    fn test_promote() {
+        let (mut tree, f6, c5) = sample();
+        tree.promote(c5);
+        let main = tree.main_line();
+        assert_eq!(*main.last().unwrap(), c5);
+        let f5 = main[1];
+        assert_eq!(tree.node(f5).children()[1], f6);
+
+        tree.promote(f6);
+        assert_eq!(tree.main_line(), [tree.root(), f5, f6]);
+        // The previous main line stays as a variation, in order.
+        assert_eq!(tree.node(f5).children().len(), 2);
+        assert_eq!(tree.node(tree.node(f5).children()[1]).children()[0], c5);
+    }
+
+    #[test]
+    fn test_depth_first() {
+        let (tree, _, _) = sample();
+        let visited: Vec<_> = tree
+            .depth_first()
+            .map(|(id, depth)| (tree.node(id).mv().map(|mv| mv.to_string()), depth))
+            .collect();
+        let expected = [
+            (None, 0),
+            (Some("f5"), 1),
+            (Some("d6"), 2),
+            (Some("c3"), 3),
+            (Some("c5"), 3),
+            (Some("f6"), 2),
+        ];
+        assert_eq!(
+            visited,
+            expected.map(|(mv, depth)| (mv.map(String::from), depth))
+        );
+    }
+
+    #[test]
+    fn test_record() {
+        let (tree, _, c5) = sample();
+        let record = tree.record(c5);
+        assert_eq!(record.cursor(), 3);
+        assert_eq!(record.board(), tree.node(c5).board());
+    }
+
+    #[test]
+    fn test_annotations() {
+        for annotation in [
+            Annotation::Brilliant,
+            Annotation::Good,
+            Annotation::Interesting,
+            Annotation::Dubious,
+            Annotation::Mistake,
+            Annotation::Blunder,
+        ] {
+            assert_eq!(annotation.to_string().parse(), Ok(annotation));
+        }
+        assert_eq!("!!!".parse::<Annotation>(), Err(ParseAnnotationError));
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let (mut tree, f6, c5) = sample();
+        tree.promote(f6);
+        let node = tree.node_mut(c5);
+        node.comment = Some("loses a tempo".to_owned());
+        node.annotation = Some(Annotation::Dubious);
+        node.evaluation = Some(-4);
+        tree.node_mut(tree.root()).comment = Some("opening".to_owned());
+
+        let json = serde_json::to_string(&tree).unwrap();
+        let loaded: GameTree = serde_json::from_str(&json).unwrap();
+        // Node ids may differ, but the structure and contents do not.
+        assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+        let f5 = loaded.main_line()[1];
+        assert_eq!(loaded.node(f5).children().len(), 2);
+
+        let illegal = json.replace("{\"Place\":18}", "{\"Place\":0}");
+        assert_ne!(illegal, json);
+        assert!(serde_json::from_str::<GameTree>(&illegal).is_err());
+    }
+}
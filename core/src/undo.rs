@@ -0,0 +1,145 @@
+use crate::{MoveError, OthelloBoard, OthelloColor, Square};
+
+/// Everything [`OthelloBoard::unmake_move`] needs to take a move back.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Undo {
+    /// `None` for a pass or null move.
+    pub(crate) square: Option<Square>,
+    pub(crate) flipped: u64,
+    /// Ply before the move, restored as is since [`OthelloBoard::ply`]
+    /// saturates.
+    pub(crate) ply: u16,
+}
+
+impl Undo {
+    #[inline]
+    pub const fn square(&self) -> Option<Square> {
+        self.square
+    }
+
+    #[inline]
+    pub const fn flipped(&self) -> u64 {
+        self.flipped
+    }
+}
+
+impl OthelloBoard {
+    /// Plays `square` like [`OthelloBoard::try_place`] and returns the
+    /// information to undo it, so a search can walk the tree on one board
+    /// instead of copying it at every node.
+    #[inline]
+    pub fn make_move(&mut self, square: Square) -> Result<Undo, MoveError> {
+        let ply = self.ply;
+        let outcome = self.try_place(square)?;
+        Ok(Undo {
+            square: Some(square),
+            flipped: outcome.flipped,
+            ply,
+        })
+    }
+
+    /// Hands the turn to the opponent even if the side to move has legal
+    /// moves. This covers real passes as well as null moves for pruning.
+    #[inline]
+    pub fn make_null_move(&mut self) -> Undo {
+        let ply = self.ply;
+        self.advance();
+        Undo {
+            square: None,
+            flipped: 0,
+            ply,
+        }
+    }
+
+    /// Takes back the move that returned `undo`, restoring the discs, side
+    /// to move and ply exactly. Undos must be applied in reverse order.
+    #[inline]
+    pub fn unmake_move(&mut self, undo: Undo) {
+        self.turn = self.turn.opponent();
+        self.ply = undo.ply;
+        let Some(square) = undo.square else {
+            return;
+        };
+        let (player, opponent) = match self.turn {
+            OthelloColor::Black => (&mut self.black, &mut self.white),
+            OthelloColor::White => (&mut self.white, &mut self.black),
+        };
+        *player ^= undo.flipped | square.mask();
+        *opponent ^= undo.flipped;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Move, testing::random_games};
+
+    #[test]
+    fn test_make_and_unmake() {
+        let mut board = OthelloBoard::new();
+        let undo = board.make_move(Square::F5).unwrap();
+        assert_eq!(undo.square(), Some(Square::F5));
+        assert_eq!(undo.flipped(), Square::E5.mask());
+        assert_eq!(board, OthelloBoard::from_transcript("f5").unwrap());
+
+        board.unmake_move(undo);
+        assert_eq!(board, OthelloBoard::new());
+    }
+
+    #[test]
+    fn test_illegal_move_leaves_board() {
+        let mut board = OthelloBoard::new();
+        assert_eq!(board.make_move(Square::D4), Err(MoveError::Occupied));
+        assert_eq!(board.make_move(Square::A1), Err(MoveError::NoFlips));
+        assert_eq!(board, OthelloBoard::new());
+    }
+
+    #[test]
+    fn test_null_move() {
+        let mut board = OthelloBoard::from_transcript("f5d6").unwrap();
+        let before = board;
+        let undo = board.make_null_move();
+        assert_eq!(board.turn, OthelloColor::White);
+        assert_eq!(board.ply, 3);
+        assert_eq!((board.black, board.white), (before.black, before.white));
+        board.unmake_move(undo);
+        assert_eq!(board, before);
+    }
+
+    #[test]
+    fn test_unmake_at_max_ply() {
+        let mut board = OthelloBoard::new();
+        board.ply = u16::MAX;
+        let before = board;
+
+        let undo = board.make_move(Square::F5).unwrap();
+        assert_eq!(board.ply, u16::MAX);
+        board.unmake_move(undo);
+        assert_eq!(board, before);
+
+        let undo = board.make_null_move();
+        board.unmake_move(undo);
+        assert_eq!(board, before);
+    }
+
+    #[test]
+    fn test_random_games_unwind() {
+        for game in random_games(3).take(100) {
+            let mut board = game.positions[0];
+            let mut undos = Vec::new();
+            for (mv, expected) in game.moves.iter().zip(&game.positions[1..]) {
+                undos.push(match *mv {
+                    Move::Place(square) => board.make_move(square).unwrap(),
+                    Move::Pass => board.make_null_move(),
+                });
+                assert_eq!(board, *expected);
+            }
+
+            for expected in game.positions.iter().rev().skip(1) {
+                board.unmake_move(undos.pop().unwrap());
+                assert_eq!(board, *expected);
+            }
+            assert_eq!(board, OthelloBoard::new());
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Nodes per second of [`engine::search_parallel`] by thread count.
+//!
+//! Throughput is the node count of one calibration run, so the reported
+//! elements per second are approximate nodes per second: Lazy SMP helpers
+//! search a slightly different number of nodes on every run.
+
+use core::OthelloBoard;
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use engine::{SearchLimits, TranspositionTable, eval::PatternEvaluator, search_parallel};
+
+const TABLE_BYTES: usize = 16 << 20;
+
+fn parallel_search(c: &mut Criterion) {
+    let board =
+        OthelloBoard::from_transcript("f5d6c3d3c4f4f6f3e6e7").expect("valid opening transcript");
+    let limits = SearchLimits::depth(8);
+    let evaluator = PatternEvaluator::default();
+    let max_threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+
+    let mut group = c.benchmark_group("search_parallel");
+    group.sample_size(10);
+    for threads in [1, 2, 4, 8].into_iter().filter(|&n| n <= max_threads) {
+        let table = TranspositionTable::new(TABLE_BYTES);
+        let nodes = search_parallel(&board, &limits, &evaluator, &table, threads).nodes;
+        group.throughput(Throughput::Elements(nodes));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(threads),
+            &threads,
+            |b, &threads| {
+                b.iter(|| {
+                    table.clear();
+                    search_parallel(&board, &limits, &evaluator, &table, threads)
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parallel_search);
+criterion_main!(benches);
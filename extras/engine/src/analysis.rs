@@ -0,0 +1,209 @@
+//! Scores for every legal move, for hints and analysis boards.
+
+use core::{GameStatus, Move, OthelloBoard};
+
+use crate::{
+    SearchLimits,
+    eval::{Evaluator, PatternEvaluator},
+    search::Searcher,
+    tt::TranspositionTable,
+};
+
+/// Table size used by [`analyze`].
+const ANALYSIS_TABLE_BYTES: usize = 4 << 20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveAnalysis {
+    pub mv: Move,
+    /// Score from the side to move's point of view, from a full-window
+    /// search rather than a bound.
+    pub score: i32,
+    /// Principal variation, starting with `mv`.
+    pub pv: Vec<Move>,
+    /// Depth searched below the root, counting `mv`.
+    pub depth: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    /// Depth of the last completed iteration, `0` once the game is over.
+    pub depth: u32,
+    /// Every legal move (or a lone [`Move::Pass`]), best first.
+    pub moves: Vec<MoveAnalysis>,
+    pub nodes: u64,
+}
+
+/// Analyzes with the default [`PatternEvaluator`], see
+/// [`analyze_with_table`].
+pub fn analyze(
+    board: &OthelloBoard,
+    limits: &SearchLimits,
+    on_update: impl FnMut(&Analysis),
+) -> Analysis {
+    let table = TranspositionTable::new(ANALYSIS_TABLE_BYTES);
+    analyze_with_table(
+        board,
+        limits,
+        &PatternEvaluator::default(),
+        &table,
+        on_update,
+    )
+}
+
+/// Scores every legal move of `board` by iterative deepening, calling
+/// `on_update` after each completed iteration so a UI can show results as
+/// they improve. Each move gets a full-window search, so this costs
+/// roughly one search per legal move.
+///
+/// [`SearchLimits`] apply to the whole analysis; an iteration cut short
+/// by a limit is discarded. Returns the last completed iteration.
+pub fn analyze_with_table<E: Evaluator + ?Sized>(
+    board: &OthelloBoard,
+    limits: &SearchLimits,
+    evaluator: &E,
+    table: &TranspositionTable,
+    mut on_update: impl FnMut(&Analysis),
+) -> Analysis {
+    let mut analysis = Analysis {
+        depth: 0,
+        moves: match board.status() {
+            GameStatus::Ongoing => board
+                .legal_squares()
+                .into_iter()
+                .map(|square| MoveAnalysis {
+                    mv: Move::Place(square),
+                    score: 0,
+                    pv: Vec::new(),
+                    depth: 0,
+                })
+                .collect(),
+            GameStatus::MustPass => vec![MoveAnalysis {
+                mv: Move::Pass,
+                score: 0,
+                pv: Vec::new(),
+                depth: 0,
+            }],
+            GameStatus::Finished { .. } => {
+                return Analysis {
+                    depth: 0,
+                    moves: Vec::new(),
+                    nodes: 0,
+                };
+            }
+        },
+        nodes: 0,
+    };
+
+    table.new_search();
+    let mut searcher = Searcher::new(evaluator, table, limits, None);
+    'deepening: for depth in 1..=searcher.max_depth(board) {
+        if depth > 1 {
+            searcher.allow_stop();
+        }
+        let mut moves = analysis.moves.clone();
+        for entry in &mut moves {
+            let hint = entry.pv.get(1..).unwrap_or_default();
+            let Some((score, pv)) = searcher.search_move(board, entry.mv, depth, hint) else {
+                break 'deepening;
+            };
+            *entry = MoveAnalysis {
+                mv: entry.mv,
+                score,
+                pv,
+                depth,
+            };
+        }
+        // Stable, so equal scores keep the previous order.
+        moves.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        analysis.moves = moves;
+        analysis.depth = depth;
+        analysis.nodes = searcher.nodes();
+        on_update(&analysis);
+    }
+    analysis.nodes = searcher.nodes();
+    analysis
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        search,
+        test_util::{endgame, play_pv, solve},
+    };
+    use core::{OthelloColor, Square};
+
+    #[test]
+    fn test_every_move_is_scored() {
+        let board = OthelloBoard::from_transcript("f5d6c3d3c4f4").unwrap();
+        let mut updates = Vec::new();
+        let analysis = analyze(&board, &SearchLimits::depth(4), |update| {
+            updates.push(update.depth)
+        });
+        assert_eq!(updates, [1, 2, 3, 4]);
+        assert_eq!(analysis.depth, 4);
+        assert_eq!(
+            analysis.moves.len(),
+            board.legal_moves().count_ones() as usize
+        );
+        for entry in &analysis.moves {
+            assert_eq!(entry.depth, 4);
+            assert_eq!(entry.pv[0], entry.mv);
+            play_pv(&board, &entry.pv);
+        }
+        assert!(
+            analysis
+                .moves
+                .windows(2)
+                .all(|pair| pair[0].score >= pair[1].score)
+        );
+
+        // The best move agrees with a plain search.
+        let result = search(&board, &SearchLimits::depth(4));
+        assert_eq!(analysis.moves[0].score, result.score);
+    }
+
+    #[test]
+    fn test_exact_scores_in_endgame() {
+        for seed in 1..=2 {
+            let board = endgame(seed, 8);
+            let analysis = analyze(&board, &SearchLimits::default(), |_| {});
+            for entry in &analysis.moves {
+                let next = play_pv(&board, &[entry.mv]);
+                assert_eq!(entry.score, -solve(&next), "seed {seed} {}", entry.mv);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pass_and_finished_game() {
+        let board = OthelloBoard::from_bitboards(
+            Square::B1.mask() | Square::C1.mask(),
+            Square::A1.mask(),
+            OthelloColor::Black,
+        );
+        let analysis = analyze(&board, &SearchLimits::depth(2), |_| {});
+        assert_eq!(analysis.moves.len(), 1);
+        assert_eq!(analysis.moves[0].mv, Move::Pass);
+
+        let finished = OthelloBoard::from_bitboards(1, 1 << 63, OthelloColor::Black);
+        let mut called = false;
+        let analysis = analyze(&finished, &SearchLimits::depth(2), |_| called = true);
+        assert!(analysis.moves.is_empty());
+        assert!(!called);
+    }
+
+    #[test]
+    fn test_node_limit_keeps_completed_iteration() {
+        let board = OthelloBoard::new();
+        let analysis = analyze(&board, &SearchLimits::nodes(300), |_| {});
+        assert!(analysis.depth >= 1);
+        assert_eq!(analysis.moves.len(), 4);
+        assert!(
+            analysis
+                .moves
+                .iter()
+                .all(|entry| entry.depth == analysis.depth)
+        );
+    }
+}
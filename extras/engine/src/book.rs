@@ -0,0 +1,425 @@
+//! Opening book built from game transcripts.
+//!
+//! Positions are stored once per symmetry class, so a line and its
+//! rotations or reflections share statistics.
+
+use core::{
+    DecodeBoardError, GameStatus, OthelloBoard, Square, TranscriptError, parse_transcript,
+    testing::Rng,
+};
+use std::{cmp::Ordering, collections::HashMap, fmt};
+
+const MAGIC: &[u8; 4] = b"OBK1";
+const HEADER_LEN: usize = 10;
+const ENTRY_LEN: usize = OthelloBoard::ENCODED_LEN + 17;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BookError {
+    Transcript(TranscriptError),
+    /// The transcript stops before the end of the game, so it has no result.
+    Unfinished,
+    InvalidMagic,
+    Truncated,
+    /// Data follows the last entry.
+    TrailingBytes,
+    InvalidPosition(DecodeBoardError),
+}
+
+impl fmt::Display for BookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BookError::Transcript(err) => err.fmt(f),
+            BookError::Unfinished => f.write_str("game is not finished"),
+            BookError::InvalidMagic => f.write_str("not an opening book file"),
+            BookError::Truncated => f.write_str("book file is truncated"),
+            BookError::TrailingBytes => f.write_str("unexpected data after the last book entry"),
+            BookError::InvalidPosition(err) => write!(f, "invalid book position: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for BookError {}
+
+impl From<TranscriptError> for BookError {
+    fn from(err: TranscriptError) -> Self {
+        BookError::Transcript(err)
+    }
+}
+
+/// Statistics of one position, from the point of view of the side to move.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct BookEntry {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    /// Engine score, if one has been recorded.
+    pub score: Option<i32>,
+}
+
+impl BookEntry {
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+}
+
+/// A move found in the book, with statistics from the point of view of the
+/// player making it.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BookMove {
+    pub square: Square,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    pub score: Option<i32>,
+}
+
+impl BookMove {
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Weight used by [`Book::choose_move`]: popular moves that score well
+    /// are picked most often, but every book move stays possible.
+    pub fn weight(&self) -> u64 {
+        2 * self.wins as u64 + self.draws as u64 + 1
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Book {
+    max_ply: u16,
+    positions: HashMap<OthelloBoard, BookEntry>,
+}
+
+/// Book key: the canonical position with the ply counter cleared.
+fn key(board: &OthelloBoard) -> OthelloBoard {
+    let (canonical, _) = board.canonical();
+    OthelloBoard::from_bitboards(canonical.black, canonical.white, canonical.turn)
+}
+
+impl Book {
+    /// Creates an empty book recording positions up to `max_ply` plies into
+    /// each game.
+    pub fn new(max_ply: u16) -> Self {
+        Book {
+            max_ply,
+            positions: HashMap::new(),
+        }
+    }
+
+    pub fn max_ply(&self) -> u16 {
+        self.max_ply
+    }
+
+    /// Number of distinct positions, up to symmetry.
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Replays a complete game from the initial position and credits its
+    /// result to every position along the way.
+    pub fn add_game(&mut self, moves: impl IntoIterator<Item = Square>) -> Result<(), BookError> {
+        let mut board = OthelloBoard::new();
+        let mut line = vec![board];
+        board.play_moves_with(moves, |position| line.push(*position))?;
+        let GameStatus::Finished { winner, .. } = board.status() else {
+            return Err(BookError::Unfinished);
+        };
+
+        for position in line.iter().filter(|position| position.ply <= self.max_ply) {
+            let entry = self.positions.entry(key(position)).or_default();
+            match winner {
+                None => entry.draws += 1,
+                Some(color) if color == position.turn => entry.wins += 1,
+                Some(_) => entry.losses += 1,
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses and adds a transcript such as `f5d6c3...`, see
+    /// [`Book::add_game`].
+    pub fn add_transcript(&mut self, transcript: &str) -> Result<(), BookError> {
+        self.add_game(parse_transcript(transcript)?)
+    }
+
+    /// Records an engine score for `board`, from the side to move's point of
+    /// view. Positions not yet in the book are added without games.
+    pub fn set_score(&mut self, board: &OthelloBoard, score: i32) {
+        self.positions.entry(key(board)).or_default().score = Some(score);
+    }
+
+    pub fn get(&self, board: &OthelloBoard) -> Option<&BookEntry> {
+        self.positions.get(&key(board))
+    }
+
+    /// Legal moves of `board` that lead to book positions, in square order.
+    pub fn moves(&self, board: &OthelloBoard) -> Vec<BookMove> {
+        board
+            .legal_squares()
+            .into_iter()
+            .filter_map(|square| {
+                let mut next = *board;
+                next.try_place(square).ok()?;
+                let entry = self.get(&next)?;
+                // The entry is from the opponent's point of view.
+                Some(BookMove {
+                    square,
+                    wins: entry.losses,
+                    draws: entry.draws,
+                    losses: entry.wins,
+                    score: entry.score.map(|score| -score),
+                })
+            })
+            .collect()
+    }
+
+    /// Picks a book move at random, weighted by [`BookMove::weight`]. The same
+    /// `seed` always picks the same move. Returns `None` out of book.
+    pub fn choose_move(&self, board: &OthelloBoard, seed: u64) -> Option<Square> {
+        let moves = self.moves(board);
+        let total: u64 = moves.iter().map(BookMove::weight).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut pick = Rng::new(seed).next_u64() % total;
+        moves.into_iter().find_map(|mv| {
+            if pick < mv.weight() {
+                Some(mv.square)
+            } else {
+                pick -= mv.weight();
+                None
+            }
+        })
+    }
+
+    /// Compact binary encoding: a header (magic, max ply, entry count)
+    /// followed by fixed-size entries sorted by position. Each entry is the
+    /// position as in [`OthelloBoard::to_bytes`], then wins, draws, losses
+    /// and score as little-endian 32-bit integers, then `1` if the score is
+    /// set and `0` otherwise.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut entries: Vec<_> = self
+            .positions
+            .iter()
+            .map(|(board, entry)| (board.to_bytes(), entry))
+            .collect();
+        entries.sort_unstable_by_key(|(bytes, _)| *bytes);
+
+        let mut buf = Vec::with_capacity(HEADER_LEN + entries.len() * ENTRY_LEN);
+        buf.extend_from_slice(MAGIC);
+        buf.extend_from_slice(&self.max_ply.to_le_bytes());
+        buf.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+        for (bytes, entry) in entries {
+            buf.extend_from_slice(&bytes);
+            buf.extend_from_slice(&entry.wins.to_le_bytes());
+            buf.extend_from_slice(&entry.draws.to_le_bytes());
+            buf.extend_from_slice(&entry.losses.to_le_bytes());
+            buf.extend_from_slice(&entry.score.unwrap_or(0).to_le_bytes());
+            buf.push(u8::from(entry.score.is_some()));
+        }
+        buf
+    }
+
+    pub fn from_bytes(buf: &[u8]) -> Result<Self, BookError> {
+        if buf.len() < HEADER_LEN {
+            return Err(BookError::Truncated);
+        }
+        if &buf[..4] != MAGIC {
+            return Err(BookError::InvalidMagic);
+        }
+        let max_ply = u16::from_le_bytes([buf[4], buf[5]]);
+        let count = u32::from_le_bytes([buf[6], buf[7], buf[8], buf[9]]) as usize;
+        let body = &buf[HEADER_LEN..];
+        match body.len().cmp(&count.saturating_mul(ENTRY_LEN)) {
+            Ordering::Less => return Err(BookError::Truncated),
+            Ordering::Greater => return Err(BookError::TrailingBytes),
+            Ordering::Equal => {}
+        }
+
+        let mut book = Book::new(max_ply);
+        for chunk in body.chunks_exact(ENTRY_LEN) {
+            let (position, stats) = chunk.split_at(OthelloBoard::ENCODED_LEN);
+            let mut bytes = [0; OthelloBoard::ENCODED_LEN];
+            bytes.copy_from_slice(position);
+            let board = OthelloBoard::from_bytes(&bytes).map_err(BookError::InvalidPosition)?;
+            let int = |i: usize| {
+                let mut word = [0; 4];
+                word.copy_from_slice(&stats[i * 4..i * 4 + 4]);
+                word
+            };
+            let score = i32::from_le_bytes(int(3));
+            book.positions.insert(
+                key(&board),
+                BookEntry {
+                    wins: u32::from_le_bytes(int(0)),
+                    draws: u32::from_le_bytes(int(1)),
+                    losses: u32::from_le_bytes(int(2)),
+                    score: (stats[16] != 0).then_some(score),
+                },
+            );
+        }
+        Ok(book)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{Move, Symmetry, testing::random_games};
+
+    /// A random game as a transcript, without its passes.
+    fn random_game(seed: u64) -> Vec<Square> {
+        let game = random_games(seed).next().unwrap();
+        game.moves
+            .into_iter()
+            .filter_map(|mv| match mv {
+                Move::Place(square) => Some(square),
+                Move::Pass => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_counts() {
+        let mut book = Book::new(10);
+        for seed in 1..=5 {
+            book.add_game(random_game(seed)).unwrap();
+        }
+        let root = book.get(&OthelloBoard::new()).unwrap();
+        assert_eq!(root.games(), 5);
+
+        // Every game passes through one of the first moves, and results
+        // flip to the mover's point of view.
+        let moves = book.moves(&OthelloBoard::new());
+        assert!(!moves.is_empty());
+        for mv in &moves {
+            assert_eq!(mv.games(), 5);
+            assert_eq!(
+                (mv.wins, mv.draws, mv.losses),
+                (root.wins, root.draws, root.losses)
+            );
+        }
+
+        // Nothing past the ply limit is stored.
+        let mut deep = OthelloBoard::new();
+        deep.play_moves(random_game(1)[..12].iter().copied())
+            .unwrap();
+        assert_eq!(book.get(&deep), None);
+    }
+
+    #[test]
+    fn test_symmetric_lines_share_entries() {
+        // The initial position survives a half turn, so the rotated game is
+        // legal too.
+        let game = random_game(3);
+        let rotated: Vec<_> = game
+            .iter()
+            .map(|square| square.transform(Symmetry::Rotate180))
+            .collect();
+        let mut book = Book::new(60);
+        book.add_game(game.iter().copied()).unwrap();
+        book.add_game(rotated.iter().copied()).unwrap();
+
+        let mut board = OthelloBoard::new();
+        board.play_moves(game[..6].iter().copied()).unwrap();
+        assert_eq!(book.get(&board).unwrap().games(), 2);
+
+        // Queried from the rotated line, moves come back rotated.
+        let mut rotated_board = OthelloBoard::new();
+        rotated_board
+            .play_moves(rotated[..6].iter().copied())
+            .unwrap();
+        let squares: Vec<_> = book
+            .moves(&rotated_board)
+            .iter()
+            .map(|mv| mv.square)
+            .collect();
+        assert!(squares.contains(&rotated[6]));
+    }
+
+    #[test]
+    fn test_weighted_choice() {
+        let mut book = Book::new(3);
+        for seed in 1..=40 {
+            book.add_game(random_game(seed)).unwrap();
+        }
+        // Every first move is equivalent, so all games pass through f5.
+        let board = OthelloBoard::from_transcript("f5").unwrap();
+        let moves = book.moves(&board);
+        assert!(moves.len() > 1);
+        assert_eq!(moves.iter().map(BookMove::games).sum::<u32>(), 40);
+
+        let total: u64 = moves.iter().map(BookMove::weight).sum();
+        for mv in &moves {
+            let picks = (0..2000)
+                .filter(|&seed| book.choose_move(&board, seed) == Some(mv.square))
+                .count() as f64;
+            let expected = 2000.0 * mv.weight() as f64 / total as f64;
+            assert!((picks - expected).abs() < 100.0, "{picks} vs {expected}");
+        }
+        assert_eq!(book.choose_move(&board, 7), book.choose_move(&board, 7));
+
+        let out_of_book = OthelloBoard::from_transcript("f5d6c3d3c4f4").unwrap();
+        assert_eq!(book.choose_move(&out_of_book, 0), None);
+    }
+
+    #[test]
+    fn test_scores() {
+        let mut book = Book::new(4);
+        let board = OthelloBoard::from_transcript("f5").unwrap();
+        book.set_score(&board, 12);
+        assert_eq!(book.get(&board).unwrap().score, Some(12));
+        assert_eq!(book.get(&board).unwrap().games(), 0);
+        let moves = book.moves(&OthelloBoard::new());
+        assert_eq!(moves.len(), 4);
+        assert!(moves.iter().all(|mv| mv.score == Some(-12)));
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let mut book = Book::new(12);
+        for seed in 1..=5 {
+            book.add_game(random_game(seed)).unwrap();
+        }
+        book.set_score(&OthelloBoard::new(), -3);
+        // Every score is representable, including the extremes.
+        book.set_score(&OthelloBoard::from_transcript("f5").unwrap(), i32::MIN);
+        let bytes = book.to_bytes();
+        assert_eq!(bytes.len(), HEADER_LEN + book.len() * ENTRY_LEN);
+        assert_eq!(Book::from_bytes(&bytes).unwrap(), book);
+        // Encoding does not depend on hash map order.
+        assert_eq!(Book::from_bytes(&bytes).unwrap().to_bytes(), bytes);
+
+        assert_eq!(
+            Book::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(BookError::Truncated)
+        );
+        assert_eq!(Book::from_bytes(b"nope"), Err(BookError::Truncated));
+        let mut padded = bytes.clone();
+        padded.push(0);
+        assert_eq!(Book::from_bytes(&padded), Err(BookError::TrailingBytes));
+        assert_eq!(
+            Book::from_bytes(b"OBK0\0\0\0\0\0\0"),
+            Err(BookError::InvalidMagic)
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut book = Book::new(10);
+        assert_eq!(book.add_transcript("f5d6"), Err(BookError::Unfinished));
+        assert!(matches!(
+            book.add_transcript("f5f5"),
+            Err(BookError::Transcript(TranscriptError::IllegalMove {
+                index: 1,
+                ..
+            }))
+        ));
+        assert!(book.is_empty());
+    }
+}
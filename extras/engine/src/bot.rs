@@ -0,0 +1,313 @@
+//! Computer players with adjustable strength and style.
+
+use core::{GameStatus, Move, OthelloBoard, testing::Rng};
+use std::time::Duration;
+
+use crate::{
+    SearchLimits,
+    eval::{Evaluator, FeatureWeights, PatternEvaluator},
+    mcts::Mcts,
+    search_with_table,
+    tt::TranspositionTable,
+};
+
+/// Table size of a [`SearchBot`].
+const BOT_TABLE_BYTES: usize = 4 << 20;
+
+/// Anything that can pick a move in a game.
+pub trait Bot {
+    /// Returns the move to play for the side to move: [`Move::Pass`] when
+    /// it has no legal move, `None` once the game is over.
+    fn choose_move(&mut self, board: &OthelloBoard) -> Option<Move>;
+}
+
+impl Bot for Mcts {
+    fn choose_move(&mut self, board: &OthelloBoard) -> Option<Move> {
+        self.search(board).best_move
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Difficulty {
+    Beginner,
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 5] = [
+        Difficulty::Beginner,
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+    ];
+
+    /// Preset settings. `Hard` and `Expert` use time limits, which need the
+    /// system clock; see [`SearchLimits`].
+    pub fn settings(self) -> DifficultySettings {
+        let (depth, time, margin, blunder_chance) = match self {
+            Difficulty::Beginner => (Some(1), None, 200, 0.25),
+            Difficulty::Easy => (Some(2), None, 80, 0.1),
+            Difficulty::Medium => (Some(4), None, 30, 0.03),
+            Difficulty::Hard => (Some(8), Some(Duration::from_secs(1)), 0, 0.0),
+            Difficulty::Expert => (None, Some(Duration::from_secs(3)), 0, 0.0),
+        };
+        DifficultySettings {
+            limits: SearchLimits {
+                depth,
+                nodes: None,
+                time,
+            },
+            margin,
+            blunder_chance,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct DifficultySettings {
+    pub limits: SearchLimits,
+    /// Any move scoring within this much of the best one may be played,
+    /// chosen uniformly. `0` always plays the best move.
+    pub margin: i32,
+    /// Probability of playing a uniformly random legal move instead.
+    pub blunder_chance: f64,
+}
+
+/// Playing styles, as evaluators for a [`SearchBot`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Personality {
+    /// The default [`PatternEvaluator`].
+    Balanced,
+    /// Maximizes its disc count, with no regard for position.
+    Greedy,
+    /// Keeps its own options open and restricts the opponent's.
+    Mobility,
+    /// Goes for corners above everything else.
+    CornerGrabber,
+}
+
+impl Personality {
+    pub fn evaluator(self) -> PatternEvaluator {
+        let weights = |mobility, potential_mobility, corners, x_squares, c_squares, discs| {
+            let weights = FeatureWeights {
+                mobility,
+                potential_mobility,
+                corners,
+                x_squares,
+                c_squares,
+                parity: 0,
+                discs,
+            };
+            PatternEvaluator {
+                opening: weights,
+                endgame: weights,
+            }
+        };
+        match self {
+            Personality::Balanced => PatternEvaluator::default(),
+            Personality::Greedy => weights(0, 0, 0, 0, 0, 10),
+            Personality::Mobility => weights(25, 10, 30, -20, -5, -2),
+            Personality::CornerGrabber => weights(3, 1, 300, -60, -20, 1),
+        }
+    }
+}
+
+/// An alpha-beta bot weakened by its [`DifficultySettings`]. Seeded, so a
+/// bot with the same seed makes the same choices.
+pub struct SearchBot<E = PatternEvaluator> {
+    evaluator: E,
+    settings: DifficultySettings,
+    rng: Rng,
+    table: TranspositionTable,
+}
+
+impl SearchBot {
+    pub fn new(difficulty: Difficulty, personality: Personality, seed: u64) -> Self {
+        SearchBot::with_settings(personality.evaluator(), difficulty.settings(), seed)
+    }
+}
+
+impl<E: Evaluator> SearchBot<E> {
+    pub fn with_settings(evaluator: E, settings: DifficultySettings, seed: u64) -> Self {
+        SearchBot {
+            evaluator,
+            settings,
+            rng: Rng::new(seed),
+            table: TranspositionTable::new(BOT_TABLE_BYTES),
+        }
+    }
+
+    pub fn settings(&self) -> &DifficultySettings {
+        &self.settings
+    }
+
+    /// Scores every legal move with a search one ply shallower than the
+    /// limit, from the side to move's point of view.
+    fn score_moves(&self, board: &OthelloBoard) -> Vec<(Move, i32)> {
+        let limits = SearchLimits {
+            depth: self
+                .settings
+                .limits
+                .depth
+                .map(|depth| depth.saturating_sub(1)),
+            ..self.settings.limits
+        };
+        board
+            .legal_squares()
+            .into_iter()
+            .filter_map(|square| {
+                let mut next = *board;
+                next.try_place(square).ok()?;
+                let score = if limits.depth == Some(0) {
+                    match next.status() {
+                        GameStatus::Finished { .. } => crate::final_score(&next),
+                        _ => self.evaluator.evaluate(&next),
+                    }
+                } else {
+                    search_with_table(&next, &limits, &self.evaluator, &self.table).score
+                };
+                Some((Move::Place(square), -score))
+            })
+            .collect()
+    }
+}
+
+impl<E: Evaluator> Bot for SearchBot<E> {
+    fn choose_move(&mut self, board: &OthelloBoard) -> Option<Move> {
+        match board.status() {
+            GameStatus::Finished { .. } => return None,
+            GameStatus::MustPass => return Some(Move::Pass),
+            GameStatus::Ongoing => {}
+        }
+
+        if self.rng.chance(self.settings.blunder_chance) {
+            let moves: Vec<_> = board.legal_squares().into_iter().collect();
+            return Some(Move::Place(moves[self.rng.below(moves.len())]));
+        }
+        if self.settings.margin == 0 {
+            return search_with_table(board, &self.settings.limits, &self.evaluator, &self.table)
+                .best_move;
+        }
+
+        let scored = self.score_moves(board);
+        let best = scored.iter().map(|&(_, score)| score).max()?;
+        let candidates: Vec<_> = scored
+            .into_iter()
+            .filter(|&(_, score)| score >= best - self.settings.margin)
+            .collect();
+        Some(candidates[self.rng.below(candidates.len())].0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcts::MctsConfig;
+    use core::{OthelloColor, Square};
+
+    fn settings(depth: u32, margin: i32, blunder_chance: f64) -> DifficultySettings {
+        DifficultySettings {
+            limits: SearchLimits::depth(depth),
+            margin,
+            blunder_chance,
+        }
+    }
+
+    #[test]
+    fn test_presets_play_legal_moves() {
+        let board = OthelloBoard::from_transcript("f5d6c3d3c4f4f6").unwrap();
+        for difficulty in [Difficulty::Beginner, Difficulty::Easy, Difficulty::Medium] {
+            let mut bot = SearchBot::new(difficulty, Personality::Balanced, 1);
+            let Some(Move::Place(square)) = bot.choose_move(&board) else {
+                panic!("{difficulty:?} did not place");
+            };
+            assert!(board.can_place_square(square));
+        }
+    }
+
+    #[test]
+    fn test_pass_and_finished_game() {
+        let mut bot = SearchBot::new(Difficulty::Beginner, Personality::Greedy, 1);
+        let board = OthelloBoard::from_bitboards(
+            Square::B1.mask() | Square::C1.mask(),
+            Square::A1.mask(),
+            OthelloColor::Black,
+        );
+        assert_eq!(bot.choose_move(&board), Some(Move::Pass));
+        let finished = OthelloBoard::from_bitboards(1, 1 << 63, OthelloColor::Black);
+        assert_eq!(bot.choose_move(&finished), None);
+    }
+
+    #[test]
+    fn test_personalities() {
+        // h1 takes a corner and flips g1; a4 flips three discs.
+        let board = OthelloBoard::from_bitboards(
+            Square::F1.mask() | Square::E4.mask(),
+            Square::G1.mask() | Square::B4.mask() | Square::C4.mask() | Square::D4.mask(),
+            OthelloColor::Black,
+        );
+        let choose = |personality: Personality| {
+            SearchBot::with_settings(personality.evaluator(), settings(1, 0, 0.0), 1)
+                .choose_move(&board)
+        };
+        assert_eq!(choose(Personality::Greedy), Some(Move::Place(Square::A4)));
+        assert_eq!(
+            choose(Personality::CornerGrabber),
+            Some(Move::Place(Square::H1))
+        );
+    }
+
+    #[test]
+    fn test_margin_and_blunders_vary_play() {
+        let board = OthelloBoard::from_transcript("f5d6c3d3c4f4f6").unwrap();
+        let distinct = |settings: DifficultySettings| {
+            (0..20)
+                .map(|seed| {
+                    SearchBot::with_settings(PatternEvaluator::default(), settings, seed)
+                        .choose_move(&board)
+                })
+                .collect::<std::collections::HashSet<_>>()
+                .len()
+        };
+        assert_eq!(distinct(settings(3, 0, 0.0)), 1);
+        assert!(distinct(settings(3, 1000, 0.0)) > 1);
+        assert!(distinct(settings(3, 0, 1.0)) > 1);
+    }
+
+    #[test]
+    fn test_seeded_bots_repeat() {
+        let board = OthelloBoard::from_transcript("f5d6c3d3c4f4f6").unwrap();
+        let play = |seed| {
+            let mut bot = SearchBot::new(Difficulty::Beginner, Personality::Mobility, seed);
+            (0..10).map(|_| bot.choose_move(&board)).collect::<Vec<_>>()
+        };
+        assert_eq!(play(5), play(5));
+    }
+
+    #[test]
+    fn test_bots_as_trait_objects() {
+        let mut bots: Vec<Box<dyn Bot>> = vec![
+            Box::new(SearchBot::new(
+                Difficulty::Easy,
+                Personality::CornerGrabber,
+                1,
+            )),
+            Box::new(Mcts::new(MctsConfig {
+                iterations: 200,
+                ..MctsConfig::default()
+            })),
+        ];
+        // Two bots play a whole game against each other.
+        let mut board = OthelloBoard::new();
+        let mut turn = 0;
+        while let Some(mv) = bots[turn % 2].choose_move(&board) {
+            board.play(mv).unwrap();
+            turn += 1;
+        }
+        assert!(board.is_finished());
+    }
+}
@@ -0,0 +1,352 @@
+//! Perfect play for the last empty squares.
+//!
+//! Scores are final disc differences (side to move minus opponent, empty
+//! squares not counted) rather than [`crate::SCORE_WIN`] based scores.
+
+use core::{Bitboard, Move, OthelloBoard, Square};
+
+/// Above this many empties moves are ordered by opponent mobility
+/// ("fastest first"); below it the cheaper parity ordering is used alone.
+const FASTEST_FIRST_EMPTIES: u32 = 7;
+
+const QUADRANTS: [u64; 4] = [
+    0x0000_0000_0f0f_0f0f,
+    0x0000_0000_f0f0_f0f0,
+    0x0f0f_0f0f_0000_0000,
+    0xf0f0_f0f0_0000_0000,
+];
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SolveMode {
+    /// Only proves whether the side to move wins, draws or loses. The score
+    /// is `1`, `0` or `-1`. Much faster than [`SolveMode::Exact`].
+    WinLossDraw,
+    /// Computes the exact final disc difference.
+    Exact,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveResult {
+    /// `None` once the game is over.
+    pub best_move: Option<Move>,
+    /// Score from the side to move's point of view, see [`SolveMode`].
+    pub score: i32,
+    /// A perfect line starting with `best_move`. In
+    /// [`SolveMode::WinLossDraw`] it keeps the result but may not maximize
+    /// the disc difference.
+    pub pv: Vec<Move>,
+    pub nodes: u64,
+}
+
+/// Solves the position to the end of the game. Intended for about 20 or
+/// fewer empty squares.
+pub fn solve(board: &OthelloBoard, mode: SolveMode) -> SolveResult {
+    let mut solver = Solver { nodes: 0 };
+    let mut pv = Vec::new();
+    let score = match mode {
+        SolveMode::WinLossDraw => solver.negamax(board, -1, 1, &mut pv).signum(),
+        SolveMode::Exact => solver.negamax(board, -64, 64, &mut pv),
+    };
+    SolveResult {
+        best_move: pv.first().copied(),
+        score,
+        pv,
+        nodes: solver.nodes,
+    }
+}
+
+#[inline]
+fn disc_diff(board: &OthelloBoard) -> i32 {
+    let (player, opponent) = board.get_current_player_and_opponent();
+    player.count_ones() as i32 - opponent.count_ones() as i32
+}
+
+struct Solver {
+    nodes: u64,
+}
+
+impl Solver {
+    fn negamax(
+        &mut self,
+        board: &OthelloBoard,
+        mut alpha: i32,
+        beta: i32,
+        pv: &mut Vec<Move>,
+    ) -> i32 {
+        self.nodes += 1;
+        pv.clear();
+
+        let empty = !(board.black | board.white);
+        match empty.count_ones() {
+            1 => return self.last_square(board, empty, pv),
+            2 | 3 => return self.few_squares(board, empty, alpha, beta, pv),
+            _ => {}
+        }
+
+        let moves = board.legal_moves();
+        if moves == 0 {
+            let mut next = *board;
+            if !next.pass() {
+                return disc_diff(board);
+            }
+            let mut child_pv = Vec::new();
+            let score = -self.negamax(&next, -beta, -alpha, &mut child_pv);
+            pv.push(Move::Pass);
+            pv.extend(child_pv);
+            return score;
+        }
+
+        let mut child_pv = Vec::new();
+        let mut best = -65;
+        for (square, next) in ordered_children(board, moves, empty) {
+            let score = -self.negamax(&next, -beta, -alpha, &mut child_pv);
+            if score > best {
+                best = score;
+                pv.clear();
+                pv.push(Move::Place(square));
+                pv.extend_from_slice(&child_pv);
+                if score > alpha {
+                    alpha = score;
+                    if alpha >= beta {
+                        break;
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// With one empty square left there is no choice to make: either side
+    /// fills it if it can, otherwise the game is over.
+    fn last_square(&mut self, board: &OthelloBoard, empty: u64, pv: &mut Vec<Move>) -> i32 {
+        let Some(square) = Square::from_mask(empty) else {
+            return disc_diff(board);
+        };
+        let mut next = *board;
+        if next.try_place(square).is_ok() {
+            self.nodes += 1;
+            pv.push(Move::Place(square));
+            return -disc_diff(&next);
+        }
+        next.turn = board.turn.opponent();
+        if next.try_place(square).is_ok() {
+            self.nodes += 1;
+            pv.extend([Move::Pass, Move::Place(square)]);
+            return disc_diff(&next);
+        }
+        disc_diff(board)
+    }
+
+    /// Solves two or three empties without allocating: squares are tried
+    /// in parity order straight from the empty mask, and the line is built
+    /// in `pv` with the best one kept on the stack. Appends to `pv`.
+    fn few_squares(
+        &mut self,
+        board: &OthelloBoard,
+        empty: u64,
+        mut alpha: i32,
+        beta: i32,
+        pv: &mut Vec<Move>,
+    ) -> i32 {
+        let start = pv.len();
+        // Three moves, each possibly preceded by a pass.
+        let mut best_line = [Move::Pass; 6];
+        let mut best_len = 0;
+        let mut best = -65;
+
+        let odd = odd_regions(empty);
+        let squares = Bitboard(empty & odd)
+            .into_iter()
+            .chain(Bitboard(empty & !odd));
+        for square in squares {
+            let mut next = *board;
+            if next.try_place(square).is_err() {
+                continue;
+            }
+            self.nodes += 1;
+            pv.truncate(start);
+            pv.push(Move::Place(square));
+            let score = -self.tail(&next, empty & !square.mask(), -beta, -alpha, pv);
+            if score > best {
+                best = score;
+                best_len = pv.len() - start;
+                best_line[..best_len].copy_from_slice(&pv[start..]);
+                if score > alpha {
+                    alpha = score;
+                    if alpha >= beta {
+                        break;
+                    }
+                }
+            }
+        }
+
+        pv.truncate(start);
+        if best == -65 {
+            let mut next = *board;
+            if !next.pass() {
+                return disc_diff(board);
+            }
+            self.nodes += 1;
+            pv.push(Move::Pass);
+            return -self.tail(&next, empty, -beta, -alpha, pv);
+        }
+        pv.extend_from_slice(&best_line[..best_len]);
+        best
+    }
+
+    /// Dispatches a position with one to three empties.
+    #[inline]
+    fn tail(
+        &mut self,
+        board: &OthelloBoard,
+        empty: u64,
+        alpha: i32,
+        beta: i32,
+        pv: &mut Vec<Move>,
+    ) -> i32 {
+        if empty.count_ones() == 1 {
+            self.last_square(board, empty, pv)
+        } else {
+            self.few_squares(board, empty, alpha, beta, pv)
+        }
+    }
+}
+
+/// Quadrants holding an odd number of empties.
+#[inline]
+fn odd_regions(empty: u64) -> u64 {
+    QUADRANTS
+        .iter()
+        .filter(|&&quadrant| (empty & quadrant).count_ones() % 2 == 1)
+        .fold(0, |mask, quadrant| mask | quadrant)
+}
+
+/// Children ordered by parity (odd empty regions first) and, with enough
+/// empties left, by the opponent's resulting mobility.
+fn ordered_children(board: &OthelloBoard, moves: u64, empty: u64) -> Vec<(Square, OthelloBoard)> {
+    let odd_regions = odd_regions(empty);
+    let fastest_first = empty.count_ones() > FASTEST_FIRST_EMPTIES;
+
+    let mut children: Vec<(u32, Square, OthelloBoard)> = Bitboard(moves)
+        .into_iter()
+        .filter_map(|square| {
+            let mut next = *board;
+            next.try_place(square).ok()?;
+            let mobility = if fastest_first {
+                next.legal_moves().count_ones()
+            } else {
+                0
+            };
+            let parity = u32::from(odd_regions & square.mask() == 0);
+            Some((mobility * 2 + parity, square, next))
+        })
+        .collect();
+    children.sort_by_key(|&(key, _, _)| key);
+    children
+        .into_iter()
+        .map(|(_, square, next)| (square, next))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{disc_diff, endgame, minimax, play_pv};
+    use core::OthelloColor;
+
+    #[test]
+    fn test_exact_matches_minimax() {
+        for seed in 1..=6 {
+            let board = endgame(seed, 10);
+            let result = solve(&board, SolveMode::Exact);
+            assert_eq!(result.score, minimax(&board, disc_diff), "seed {seed}");
+
+            // Following the line reproduces the score.
+            let end = play_pv(&board, &result.pv);
+            assert!(end.is_finished());
+            let diff = disc_diff(&end);
+            let sign = if end.get_turn() == board.get_turn() {
+                1
+            } else {
+                -1
+            };
+            assert_eq!(diff * sign, result.score, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn test_win_loss_draw_matches_exact() {
+        for seed in 1..=6 {
+            let board = endgame(seed, 12);
+            let exact = solve(&board, SolveMode::Exact);
+            let wld = solve(&board, SolveMode::WinLossDraw);
+            assert_eq!(wld.score, exact.score.signum(), "seed {seed}");
+            assert!(wld.nodes <= exact.nodes);
+
+            let end = play_pv(&board, &wld.pv);
+            assert!(end.is_finished());
+        }
+    }
+
+    #[test]
+    fn test_last_square() {
+        // Black fills h1 and flips g1.
+        let board = OthelloBoard::from_bitboards(
+            u64::MAX & !(Square::G1.mask() | Square::H1.mask()) & !Square::A1.mask(),
+            Square::G1.mask() | Square::A1.mask(),
+            OthelloColor::Black,
+        );
+        let result = solve(&board, SolveMode::Exact);
+        assert_eq!(result.pv, [Move::Place(Square::H1)]);
+        assert_eq!(result.score, 63 - 1);
+
+        // Neither side can use the last square.
+        let board = OthelloBoard::from_bitboards(
+            u64::MAX & !Square::A1.mask() & !Square::G2.mask(),
+            Square::G2.mask(),
+            OthelloColor::White,
+        );
+        let result = solve(&board, SolveMode::Exact);
+        assert_eq!(result.best_move, None);
+        assert_eq!(result.score, 1 - 62);
+    }
+
+    #[test]
+    fn test_few_empties_match_minimax() {
+        for empties in [2, 3] {
+            for seed in 1..=40 {
+                let board = endgame(seed, empties);
+                let result = solve(&board, SolveMode::Exact);
+                assert_eq!(
+                    result.score,
+                    minimax(&board, disc_diff),
+                    "seed {seed}, {empties} empties"
+                );
+
+                let end = play_pv(&board, &result.pv);
+                assert!(end.is_finished());
+                let sign = if end.get_turn() == board.get_turn() {
+                    1
+                } else {
+                    -1
+                };
+                assert_eq!(disc_diff(&end) * sign, result.score, "seed {seed}");
+
+                let wld = solve(&board, SolveMode::WinLossDraw);
+                assert_eq!(wld.score, result.score.signum(), "seed {seed}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_forced_pass() {
+        let board = OthelloBoard::from_bitboards(
+            Square::B1.mask() | Square::C1.mask(),
+            Square::A1.mask(),
+            OthelloColor::Black,
+        );
+        let result = solve(&board, SolveMode::Exact);
+        assert_eq!(result.pv, [Move::Pass, Move::Place(Square::D1)]);
+        assert_eq!(result.score, -4);
+    }
+}
@@ -0,0 +1,255 @@
+//! Static evaluation of positions that are not finished.
+//!
+//! Scores are from the side to move's point of view and must stay well
+//! inside `±`[`crate::SCORE_WIN`] so that proven results always win.
+
+use core::{OthelloBoard, OthelloColor};
+
+/// Scores a position for the side to move. Implemented for closures too, so
+/// ad hoc evaluators can be passed to [`crate::search_with`] directly.
+pub trait Evaluator {
+    fn evaluate(&self, board: &OthelloBoard) -> i32;
+}
+
+impl<F: Fn(&OthelloBoard) -> i32> Evaluator for F {
+    #[inline]
+    fn evaluate(&self, board: &OthelloBoard) -> i32 {
+        self(board)
+    }
+}
+
+/// Classic positional weights: corners are valuable, the squares next to
+/// them hand corners to the opponent.
+#[rustfmt::skip]
+pub(crate) const SQUARE_WEIGHTS: [i32; 64] = [
+    100, -20, 10,  5,  5, 10, -20, 100,
+    -20, -50, -2, -2, -2, -2, -50, -20,
+     10,  -2,  1,  1,  1,  1,  -2,  10,
+      5,  -2,  1,  0,  0,  1,  -2,   5,
+      5,  -2,  1,  0,  0,  1,  -2,   5,
+     10,  -2,  1,  1,  1,  1,  -2,  10,
+    -20, -50, -2, -2, -2, -2, -50, -20,
+    100, -20, 10,  5,  5, 10, -20, 100,
+];
+
+const FILE_A: u64 = 0x0101_0101_0101_0101;
+const FILE_H: u64 = 0x8080_8080_8080_8080;
+const CORNERS: u64 = 0x8100_0000_0000_0081;
+
+/// `(corner, x_square, c_squares)` for each corner.
+const CORNER_REGIONS: [(u64, u64, u64); 4] = [
+    (1 << 0, 1 << 9, (1 << 1) | (1 << 8)),
+    (1 << 7, 1 << 14, (1 << 6) | (1 << 15)),
+    (1 << 56, 1 << 49, (1 << 48) | (1 << 57)),
+    (1 << 63, 1 << 54, (1 << 55) | (1 << 62)),
+];
+
+fn mobility(player: u64, opponent: u64) -> i32 {
+    let mut board = OthelloBoard::from_bitboards(player, opponent, OthelloColor::Black);
+    let own = board.legal_moves().count_ones() as i32;
+    board.turn = OthelloColor::White;
+    own - board.legal_moves().count_ones() as i32
+}
+
+/// Squares adjacent to any disc in `mask`.
+#[inline]
+fn neighbours(mask: u64) -> u64 {
+    let east = (mask << 1) & !FILE_A;
+    let west = (mask >> 1) & !FILE_H;
+    let row = mask | east | west;
+    (row << 8) | (row >> 8) | east | west
+}
+
+/// Weights of the [`PatternEvaluator`] features at one point of the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeatureWeights {
+    /// Per legal move, own minus opponent's.
+    pub mobility: i32,
+    /// Per empty square next to an opponent disc, minus the same for own
+    /// discs (the opponent's frontier).
+    pub potential_mobility: i32,
+    pub corners: i32,
+    /// Per disc on an X-square (b2, g2, b7, g7) next to an empty corner.
+    pub x_squares: i32,
+    /// Per disc on a C-square (a2, b1, ...) next to an empty corner.
+    pub c_squares: i32,
+    /// Bonus when the side to move is due the last move of the game.
+    pub parity: i32,
+    pub discs: i32,
+}
+
+/// The default evaluator. Every feature is computed as own minus opponent's
+/// and weighted by interpolating between `opening` (60 empties) and
+/// `endgame` (no empties) weights.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PatternEvaluator {
+    pub opening: FeatureWeights,
+    pub endgame: FeatureWeights,
+}
+
+impl Default for PatternEvaluator {
+    fn default() -> Self {
+        PatternEvaluator {
+            opening: FeatureWeights {
+                mobility: 10,
+                potential_mobility: 4,
+                corners: 80,
+                x_squares: -40,
+                c_squares: -15,
+                parity: 0,
+                discs: -1,
+            },
+            endgame: FeatureWeights {
+                mobility: 5,
+                potential_mobility: 1,
+                corners: 60,
+                x_squares: -10,
+                c_squares: -5,
+                parity: 20,
+                discs: 4,
+            },
+        }
+    }
+}
+
+impl PatternEvaluator {
+    fn weights(&self, empties: i32) -> FeatureWeights {
+        let (o, e) = (&self.opening, &self.endgame);
+        let mix = |opening: i32, endgame: i32| (opening * empties + endgame * (60 - empties)) / 60;
+        FeatureWeights {
+            mobility: mix(o.mobility, e.mobility),
+            potential_mobility: mix(o.potential_mobility, e.potential_mobility),
+            corners: mix(o.corners, e.corners),
+            x_squares: mix(o.x_squares, e.x_squares),
+            c_squares: mix(o.c_squares, e.c_squares),
+            parity: mix(o.parity, e.parity),
+            discs: mix(o.discs, e.discs),
+        }
+    }
+}
+
+fn corner_features(player: u64, opponent: u64) -> (i32, i32, i32) {
+    let count =
+        |mask: u64| (player & mask).count_ones() as i32 - (opponent & mask).count_ones() as i32;
+    let empty = !(player | opponent);
+    let mut x_squares = 0;
+    let mut c_squares = 0;
+    for (corner, x, c) in CORNER_REGIONS {
+        if empty & corner != 0 {
+            x_squares += count(x);
+            c_squares += count(c);
+        }
+    }
+    (count(CORNERS), x_squares, c_squares)
+}
+
+impl Evaluator for PatternEvaluator {
+    fn evaluate(&self, board: &OthelloBoard) -> i32 {
+        let (player, opponent) = board.get_current_player_and_opponent();
+        let empty = !(player | opponent);
+        let empties = empty.count_ones() as i32;
+        let w = self.weights(empties.min(60));
+
+        let potential_mobility = (neighbours(opponent) & empty).count_ones() as i32
+            - (neighbours(player) & empty).count_ones() as i32;
+        let (corners, x_squares, c_squares) = corner_features(player, opponent);
+        let parity = if empties % 2 == 1 { 1 } else { -1 };
+        let discs = player.count_ones() as i32 - opponent.count_ones() as i32;
+
+        w.mobility * mobility(player, opponent)
+            + w.potential_mobility * potential_mobility
+            + w.corners * corners
+            + w.x_squares * x_squares
+            + w.c_squares * c_squares
+            + w.parity * parity
+            + w.discs * discs
+    }
+}
+
+/// Positional square table plus mobility; cheap, and useful as a baseline
+/// opponent when comparing evaluators.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SquareTableEvaluator;
+
+fn weighted(mut mask: u64) -> i32 {
+    let mut total = 0;
+    while mask != 0 {
+        total += SQUARE_WEIGHTS[mask.trailing_zeros() as usize];
+        mask &= mask - 1;
+    }
+    total
+}
+
+impl Evaluator for SquareTableEvaluator {
+    fn evaluate(&self, board: &OthelloBoard) -> i32 {
+        let (player, opponent) = board.get_current_player_and_opponent();
+        weighted(player) - weighted(opponent) + 10 * mobility(player, opponent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::{Square, Symmetry};
+
+    #[test]
+    fn test_initial_position_is_balanced() {
+        let board = OthelloBoard::new();
+        assert_eq!(PatternEvaluator::default().evaluate(&board), 0);
+        assert_eq!(SquareTableEvaluator.evaluate(&board), 0);
+    }
+
+    #[test]
+    fn test_evaluation_is_symmetric() {
+        let board = OthelloBoard::from_transcript("f5d6c3d3c4f4f6f3e6e7").unwrap();
+        let evaluator = PatternEvaluator::default();
+        let score = evaluator.evaluate(&board);
+        for symmetry in Symmetry::ALL {
+            assert_eq!(evaluator.evaluate(&board.transform(symmetry)), score);
+        }
+    }
+
+    #[test]
+    fn test_evaluation_ignores_colors() {
+        let board = OthelloBoard::from_transcript("f5d6c3d3c4f4f6").unwrap();
+        let swapped = OthelloBoard::from_bitboards(board.white, board.black, board.turn.opponent());
+        let evaluator = PatternEvaluator::default();
+        assert_eq!(evaluator.evaluate(&board), evaluator.evaluate(&swapped));
+    }
+
+    #[test]
+    fn test_corner_and_x_square() {
+        let evaluator = PatternEvaluator::default();
+        let base =
+            OthelloBoard::from_bitboards(Square::D4.mask(), Square::E5.mask(), OthelloColor::Black);
+        let corner = OthelloBoard::from_bitboards(
+            base.black | Square::A1.mask(),
+            base.white,
+            OthelloColor::Black,
+        );
+        let x_square = OthelloBoard::from_bitboards(
+            base.black | Square::B2.mask(),
+            base.white,
+            OthelloColor::Black,
+        );
+        let score = evaluator.evaluate(&base);
+        assert!(evaluator.evaluate(&corner) > score);
+        assert!(evaluator.evaluate(&x_square) < score);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        assert_eq!(
+            neighbours(Square::A1.mask()),
+            Square::B1.mask() | Square::A2.mask() | Square::B2.mask()
+        );
+        assert_eq!(neighbours(Square::H8.mask()).count_ones(), 3);
+        assert_eq!(neighbours(Square::D4.mask()).count_ones(), 8);
+    }
+
+    #[test]
+    fn test_closure_evaluator() {
+        let discs = |board: &OthelloBoard| board.count(board.get_turn()) as i32;
+        assert_eq!(discs.evaluate(&OthelloBoard::new()), 2);
+    }
+}
@@ -0,0 +1,38 @@
+use core::OthelloBoard;
+
+pub mod analysis;
+pub mod book;
+pub mod bot;
+pub mod endgame;
+pub mod eval;
+pub mod mcts;
+mod search;
+#[cfg(test)]
+mod test_util;
+mod tt;
+
+pub use eval::Evaluator;
+pub use search::{
+    SearchLimits, SearchResult, search, search_parallel, search_with, search_with_table,
+};
+pub use tt::{Bound, TranspositionTable, TtEntry};
+
+/// Finished games score at least this much (or at most its negation), so
+/// that any proven result outranks a heuristic evaluation.
+pub const SCORE_WIN: i32 = 100_000;
+
+/// Bound used as the initial search window.
+pub const SCORE_INF: i32 = 1_000_000;
+
+/// Score of a finished game from the side to move's point of view:
+/// [`SCORE_WIN`] plus the disc difference for a win, its negation for a
+/// loss and `0` for a draw.
+pub fn final_score(board: &OthelloBoard) -> i32 {
+    let (player, opponent) = board.get_current_player_and_opponent();
+    let diff = player.count_ones() as i32 - opponent.count_ones() as i32;
+    match diff.signum() {
+        1 => SCORE_WIN + diff,
+        -1 => -SCORE_WIN + diff,
+        _ => 0,
+    }
+}
@@ -0,0 +1,382 @@
+//! Monte Carlo tree search (UCT).
+//!
+//! Much weaker than [`crate::search`] at equal time, but its strength scales
+//! smoothly with the iteration budget and it needs no clock, which suits
+//! easy opponents and the browser build.
+
+use core::{Bitboard, GameStatus, Move, OthelloBoard, OthelloColor, Square, testing::Rng};
+
+const CORNERS: u64 = 0x8100_0000_0000_0081;
+const X_SQUARES: u64 = 0x0042_0000_0000_4200;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Playout {
+    /// Uniformly random moves.
+    Random,
+    /// Random moves, except that corners are always taken and X-squares
+    /// avoided when there is an alternative.
+    Guided,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct MctsConfig {
+    /// Playouts per call to [`Mcts::search`].
+    pub iterations: u32,
+    /// UCT exploration constant.
+    pub exploration: f64,
+    pub playout: Playout,
+    pub seed: u64,
+}
+
+impl Default for MctsConfig {
+    fn default() -> Self {
+        MctsConfig {
+            iterations: 10_000,
+            exploration: std::f64::consts::SQRT_2,
+            playout: Playout::Guided,
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct MctsResult {
+    /// `None` once the game is over.
+    pub best_move: Option<Move>,
+    /// Estimated result of `best_move` for the side to move, from `0.0`
+    /// (loss) to `1.0` (win); draws count half.
+    pub win_rate: f64,
+    /// Visits of the root, including those kept from earlier searches.
+    pub visits: u32,
+}
+
+struct Node {
+    board: OthelloBoard,
+    /// Move leading here from the parent.
+    mv: Option<Move>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried: Vec<Move>,
+    visits: u32,
+    /// Sum of results for the player who made `mv`.
+    reward: f64,
+}
+
+impl Node {
+    fn new(board: OthelloBoard, mv: Option<Move>, parent: Option<usize>) -> Self {
+        let untried = match board.status() {
+            GameStatus::Ongoing => board.legal_squares().into_iter().map(Move::Place).collect(),
+            GameStatus::MustPass => vec![Move::Pass],
+            GameStatus::Finished { .. } => Vec::new(),
+        };
+        Node {
+            board,
+            mv,
+            parent,
+            children: Vec::new(),
+            untried,
+            visits: 0,
+            reward: 0.0,
+        }
+    }
+}
+
+/// A UCT player. The tree is kept between calls to [`Mcts::search`], so
+/// searching a position reached by playing on from the previous one starts
+/// from the statistics already gathered for it.
+pub struct Mcts {
+    config: MctsConfig,
+    rng: Rng,
+    nodes: Vec<Node>,
+}
+
+impl Mcts {
+    pub fn new(config: MctsConfig) -> Self {
+        Mcts {
+            rng: Rng::new(config.seed),
+            config,
+            nodes: Vec::new(),
+        }
+    }
+
+    pub fn config(&self) -> &MctsConfig {
+        &self.config
+    }
+
+    /// Number of nodes in the tree.
+    pub fn tree_size(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Drops the tree, e.g. when starting a new game.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+    }
+
+    pub fn search(&mut self, board: &OthelloBoard) -> MctsResult {
+        self.reroot(board);
+        for _ in 0..self.config.iterations {
+            self.iterate();
+        }
+
+        let root = &self.nodes[0];
+        let best = root
+            .children
+            .iter()
+            .map(|&child| &self.nodes[child])
+            .max_by_key(|child| child.visits);
+        MctsResult {
+            best_move: best.and_then(|child| child.mv),
+            win_rate: best.map_or(0.0, |child| child.reward / child.visits.max(1) as f64),
+            visits: root.visits,
+        }
+    }
+
+    /// Makes `board` the root, keeping its subtree if it is the current root
+    /// or one of its children or grandchildren.
+    fn reroot(&mut self, board: &OthelloBoard) {
+        let same = |node: &Node| {
+            node.board.black == board.black
+                && node.board.white == board.white
+                && node.board.turn == board.turn
+        };
+        let found = self.nodes.first().and_then(|root| {
+            if same(root) {
+                return Some(0);
+            }
+            root.children
+                .iter()
+                .flat_map(|&child| {
+                    std::iter::once(child).chain(self.nodes[child].children.iter().copied())
+                })
+                .find(|&index| same(&self.nodes[index]))
+        });
+
+        match found {
+            Some(0) => {}
+            Some(index) => self.extract_subtree(index),
+            None => {
+                self.nodes.clear();
+                self.nodes.push(Node::new(*board, None, None));
+            }
+        }
+        self.nodes[0].board = *board;
+    }
+
+    fn extract_subtree(&mut self, root: usize) {
+        let mut old: Vec<Option<Node>> = std::mem::take(&mut self.nodes)
+            .into_iter()
+            .map(Some)
+            .collect();
+        let mut queue = std::collections::VecDeque::from([(root, None)]);
+        while let Some((index, parent)) = queue.pop_front() {
+            let new_index = self.nodes.len();
+            // Each node of a tree is reached once.
+            let Some(mut node) = old[index].take() else {
+                continue;
+            };
+            node.parent = parent;
+            queue.extend(
+                node.children
+                    .drain(..)
+                    .map(|child| (child, Some(new_index))),
+            );
+            if let Some(parent) = parent {
+                self.nodes[parent].children.push(new_index);
+            }
+            self.nodes.push(node);
+        }
+        self.nodes[0].mv = None;
+    }
+
+    fn iterate(&mut self) {
+        // Selection.
+        let mut index = 0;
+        while self.nodes[index].untried.is_empty() && !self.nodes[index].children.is_empty() {
+            index = self.select_child(index);
+        }
+
+        // Expansion.
+        if !self.nodes[index].untried.is_empty() {
+            let untried = &mut self.nodes[index].untried;
+            let mv = untried.swap_remove(self.rng.below(untried.len()));
+            let mut board = self.nodes[index].board;
+            board
+                .play(mv)
+                .expect("untried moves are legal in their node's position");
+            let child = self.nodes.len();
+            self.nodes.push(Node::new(board, Some(mv), Some(index)));
+            self.nodes[index].children.push(child);
+            index = child;
+        }
+
+        // Simulation.
+        let winner = self.playout(self.nodes[index].board);
+
+        // Backpropagation.
+        let mut current = Some(index);
+        while let Some(index) = current {
+            let parent = self.nodes[index].parent;
+            let node = &mut self.nodes[index];
+            node.visits += 1;
+            if let Some(parent) = parent {
+                let mover = self.nodes[parent].board.turn;
+                self.nodes[index].reward += match winner {
+                    Some(color) if color == mover => 1.0,
+                    Some(_) => 0.0,
+                    None => 0.5,
+                };
+            }
+            current = parent;
+        }
+    }
+
+    fn select_child(&self, index: usize) -> usize {
+        let node = &self.nodes[index];
+        let log_visits = (node.visits.max(1) as f64).ln();
+        let uct = |child: usize| {
+            let child = &self.nodes[child];
+            let visits = child.visits.max(1) as f64;
+            child.reward / visits + self.config.exploration * (log_visits / visits).sqrt()
+        };
+        let mut best = node.children[0];
+        let mut best_score = uct(best);
+        for &child in &node.children[1..] {
+            let score = uct(child);
+            if score > best_score {
+                best = child;
+                best_score = score;
+            }
+        }
+        best
+    }
+
+    /// Plays the game out and returns the winner.
+    fn playout(&mut self, mut board: OthelloBoard) -> Option<OthelloColor> {
+        loop {
+            let mut moves = board.legal_moves();
+            if moves == 0 {
+                if !board.pass() {
+                    break;
+                }
+                continue;
+            }
+            if self.config.playout == Playout::Guided {
+                if moves & CORNERS != 0 {
+                    moves &= CORNERS;
+                } else if moves & !X_SQUARES != 0 {
+                    moves &= !X_SQUARES;
+                }
+            }
+            let square = nth_square(moves, self.rng.below(moves.count_ones() as usize));
+            board
+                .try_place(square)
+                .expect("playout squares come from the legal moves");
+        }
+        match board.status() {
+            GameStatus::Finished { winner, .. } => winner,
+            _ => None,
+        }
+    }
+}
+
+fn nth_square(moves: u64, n: usize) -> Square {
+    let mut squares = Bitboard(moves).into_iter();
+    squares
+        .nth(n)
+        .expect("n is below the number of squares in moves")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        endgame::{SolveMode, solve},
+        test_util::{endgame, play_pv},
+    };
+
+    fn mcts(iterations: u32, seed: u64) -> Mcts {
+        Mcts::new(MctsConfig {
+            iterations,
+            seed,
+            ..MctsConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_returns_legal_move() {
+        let board = OthelloBoard::new();
+        let result = mcts(500, 1).search(&board);
+        let Some(Move::Place(square)) = result.best_move else {
+            panic!("expected a placement");
+        };
+        assert!(board.can_place_square(square));
+        assert_eq!(result.visits, 500);
+        assert!((0.0..=1.0).contains(&result.win_rate));
+    }
+
+    #[test]
+    fn test_seeded_search_is_reproducible() {
+        let board = OthelloBoard::from_transcript("f5d6c3d3c4f4").unwrap();
+        for seed in 1..=3 {
+            assert_eq!(
+                mcts(300, seed).search(&board),
+                mcts(300, seed).search(&board)
+            );
+        }
+    }
+
+    #[test]
+    fn test_keeps_won_endgames_won() {
+        let mut checked = 0;
+        for seed in 1..=6 {
+            let board = endgame(seed, 8);
+            if solve(&board, SolveMode::WinLossDraw).score != 1 {
+                continue;
+            }
+            checked += 1;
+            let mv = mcts(3000, seed).search(&board).best_move.unwrap();
+            let next = play_pv(&board, &[mv]);
+            assert_eq!(
+                solve(&next, SolveMode::WinLossDraw).score,
+                -1,
+                "seed {seed}"
+            );
+        }
+        assert!(checked > 0);
+    }
+
+    #[test]
+    fn test_forced_pass_and_finished_game() {
+        let board = OthelloBoard::from_bitboards(
+            Square::B1.mask() | Square::C1.mask(),
+            Square::A1.mask(),
+            OthelloColor::Black,
+        );
+        assert_eq!(mcts(50, 1).search(&board).best_move, Some(Move::Pass));
+
+        let finished = OthelloBoard::from_bitboards(1, 1 << 63, OthelloColor::Black);
+        let result = mcts(50, 1).search(&finished);
+        assert_eq!(result.best_move, None);
+        assert_eq!(result.visits, 50);
+    }
+
+    #[test]
+    fn test_tree_reuse() {
+        let mut player = mcts(400, 3);
+        let mut board = OthelloBoard::new();
+        let first = player.search(&board);
+        board.play(first.best_move.unwrap()).unwrap();
+        let reply = board.legal_squares().first().unwrap();
+        board.try_place(reply).unwrap();
+
+        let size = player.tree_size();
+        let second = player.search(&board);
+        assert!(second.visits > 400, "kept {} visits", second.visits - 400);
+        assert!(player.tree_size() < size + 400);
+
+        // An unrelated position starts from scratch.
+        let other = OthelloBoard::from_transcript("d3c3c4c5").unwrap();
+        assert_eq!(player.search(&other).visits, 400);
+    }
+}
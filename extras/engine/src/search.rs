@@ -0,0 +1,601 @@
+use core::{Move, OthelloBoard, Square};
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use core::hash;
+
+use crate::{
+    SCORE_INF,
+    eval::{self, Evaluator, PatternEvaluator},
+    final_score,
+    tt::{Bound, TranspositionTable, TtEntry},
+};
+
+const MAX_DEPTH: u32 = 60;
+
+/// Nodes between two reads of the clock.
+const CLOCK_INTERVAL: u32 = 1024;
+
+/// Stops the search when any limit is reached. With no limits set the
+/// search runs until the game is solved. The first iteration always
+/// completes so that a move is available.
+///
+/// A time limit reads the system clock, which is not available on
+/// `wasm32-unknown-unknown`; use depth or node limits there.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    pub depth: Option<u32>,
+    pub nodes: Option<u64>,
+    pub time: Option<Duration>,
+}
+
+impl SearchLimits {
+    pub const fn depth(depth: u32) -> Self {
+        SearchLimits {
+            depth: Some(depth),
+            nodes: None,
+            time: None,
+        }
+    }
+
+    pub const fn nodes(nodes: u64) -> Self {
+        SearchLimits {
+            depth: None,
+            nodes: Some(nodes),
+            time: None,
+        }
+    }
+
+    pub const fn time(time: Duration) -> Self {
+        SearchLimits {
+            depth: None,
+            nodes: None,
+            time: Some(time),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    /// `None` once the game is over.
+    pub best_move: Option<Move>,
+    /// Score from the side to move's point of view.
+    pub score: i32,
+    /// Principal variation, starting with `best_move`.
+    pub pv: Vec<Move>,
+    pub nodes: u64,
+    /// Depth of the last completed iteration.
+    pub depth: u32,
+}
+
+/// Table size used by [`search`] and [`search_with`].
+const DEFAULT_TABLE_BYTES: usize = 4 << 20;
+
+/// Searches with the default [`PatternEvaluator`].
+pub fn search(board: &OthelloBoard, limits: &SearchLimits) -> SearchResult {
+    search_with(board, limits, &PatternEvaluator::default())
+}
+
+/// Searches using `evaluator` to score positions at the horizon.
+pub fn search_with<E: Evaluator + ?Sized>(
+    board: &OthelloBoard,
+    limits: &SearchLimits,
+    evaluator: &E,
+) -> SearchResult {
+    let table = TranspositionTable::new(DEFAULT_TABLE_BYTES);
+    search_with_table(board, limits, evaluator, &table)
+}
+
+/// Like [`search_with`], but keeps positions in `table`, which can be
+/// reused across moves of a game or shared with other searches.
+pub fn search_with_table<E: Evaluator + ?Sized>(
+    board: &OthelloBoard,
+    limits: &SearchLimits,
+    evaluator: &E,
+    table: &TranspositionTable,
+) -> SearchResult {
+    table.new_search();
+    Searcher::new(evaluator, table, limits, None).iterate(board, 1)
+}
+
+/// Lazy SMP: `threads` searchers run iterative deepening on the same
+/// position, sharing only `table`. Helpers start at alternating depths so
+/// they fill the table ahead of the main thread, whose result is returned
+/// once it finishes; the helpers are then stopped.
+///
+/// Limits apply to the main thread, except that `nodes` in the result
+/// counts every thread. With one thread this is [`search_with_table`] and
+/// fully deterministic.
+pub fn search_parallel<E: Evaluator + Sync + ?Sized>(
+    board: &OthelloBoard,
+    limits: &SearchLimits,
+    evaluator: &E,
+    table: &TranspositionTable,
+    threads: usize,
+) -> SearchResult {
+    if threads <= 1 {
+        return search_with_table(board, limits, evaluator, table);
+    }
+    table.new_search();
+    let stop = AtomicBool::new(false);
+    let helper_limits = SearchLimits {
+        depth: limits.depth,
+        ..SearchLimits::default()
+    };
+    std::thread::scope(|scope| {
+        let helpers: Vec<_> = (1..threads)
+            .map(|index| {
+                let (stop, helper_limits) = (&stop, &helper_limits);
+                scope.spawn(move || {
+                    let mut helper = Searcher::new(evaluator, table, helper_limits, Some(stop));
+                    helper.can_stop = true;
+                    helper.iterate(board, 1 + index as u32 % 2).nodes
+                })
+            })
+            .collect();
+
+        let mut result = Searcher::new(evaluator, table, limits, None).iterate(board, 1);
+        stop.store(true, Ordering::Relaxed);
+        for helper in helpers {
+            result.nodes += helper.join().unwrap_or(0);
+        }
+        result
+    })
+}
+
+pub(crate) struct Searcher<'a, E: ?Sized> {
+    evaluator: &'a E,
+    table: &'a TranspositionTable,
+    /// Set by the main thread to stop Lazy SMP helpers.
+    stop: Option<&'a AtomicBool>,
+    limits: SearchLimits,
+    deadline: Option<Instant>,
+    nodes: u64,
+    /// Counts down at every node; the clock is read once it reaches zero.
+    clock_countdown: u32,
+    stopped: bool,
+    can_stop: bool,
+}
+
+impl<'a, E: Evaluator + ?Sized> Searcher<'a, E> {
+    pub(crate) fn new(
+        evaluator: &'a E,
+        table: &'a TranspositionTable,
+        limits: &SearchLimits,
+        stop: Option<&'a AtomicBool>,
+    ) -> Self {
+        Searcher {
+            evaluator,
+            table,
+            stop,
+            limits: *limits,
+            deadline: limits.time.map(|time| Instant::now() + time),
+            nodes: 0,
+            clock_countdown: CLOCK_INTERVAL,
+            stopped: false,
+            can_stop: false,
+        }
+    }
+
+    /// Deepest iteration allowed by the limits; deeper than the number of
+    /// empty squares is pointless.
+    pub(crate) fn max_depth(&self, board: &OthelloBoard) -> u32 {
+        let empties = 64 - (board.black | board.white).count_ones();
+        self.limits.depth.unwrap_or(MAX_DEPTH).min(empties).max(1)
+    }
+
+    /// Lets limits stop the search; until then every search completes.
+    pub(crate) fn allow_stop(&mut self) {
+        self.can_stop = true;
+    }
+
+    pub(crate) fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// Iterative deepening from `first_depth` up to the depth limit.
+    fn iterate(&mut self, board: &OthelloBoard, first_depth: u32) -> SearchResult {
+        let max_depth = self.max_depth(board);
+        let key = board.hash_key();
+
+        let mut result = SearchResult {
+            best_move: None,
+            score: 0,
+            pv: Vec::new(),
+            nodes: 0,
+            depth: 0,
+        };
+        for depth in first_depth.min(max_depth)..=max_depth {
+            self.can_stop |= depth > 1;
+            let mut pv = Vec::new();
+            let score = self.negamax(
+                board, key, depth, -SCORE_INF, SCORE_INF, &mut pv, &result.pv,
+            );
+            if self.stopped {
+                break;
+            }
+            result.best_move = pv.first().copied();
+            result.score = score;
+            result.pv = pv;
+            result.depth = depth;
+        }
+        result.nodes = self.nodes;
+        result
+    }
+
+    /// Plays `mv` and searches the reply with a full window, so the score
+    /// is exact. Returns the score for the side to move and the PV starting
+    /// with `mv`, or `None` if a limit stopped the search.
+    pub(crate) fn search_move(
+        &mut self,
+        board: &OthelloBoard,
+        mv: Move,
+        depth: u32,
+        pv_hint: &[Move],
+    ) -> Option<(i32, Vec<Move>)> {
+        let mut next = *board;
+        let (key, depth) = match mv {
+            Move::Pass => {
+                next.pass();
+                (next.hash_key(), depth)
+            }
+            Move::Place(square) => {
+                next.try_place(square).ok()?;
+                (next.hash_key(), depth.saturating_sub(1))
+            }
+        };
+        let mut child_pv = Vec::new();
+        let score = -self.negamax(
+            &next,
+            key,
+            depth,
+            -SCORE_INF,
+            SCORE_INF,
+            &mut child_pv,
+            pv_hint,
+        );
+        if self.stopped {
+            return None;
+        }
+        let mut pv = vec![mv];
+        pv.extend(child_pv);
+        Some((score, pv))
+    }
+
+    fn check_limits(&mut self) -> bool {
+        if self.stop.is_some_and(|stop| stop.load(Ordering::Relaxed)) {
+            self.stopped = true;
+            return true;
+        }
+        if !self.can_stop {
+            return false;
+        }
+        if self.limits.nodes.is_some_and(|max| self.nodes >= max) {
+            self.stopped = true;
+        }
+        if self.clock_countdown == 0 {
+            self.clock_countdown = CLOCK_INTERVAL;
+            if self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                self.stopped = true;
+            }
+        }
+        self.stopped
+    }
+
+    /// Alpha-beta negamax. Passes do not consume depth, so a depth of at
+    /// least the number of empty squares searches every line to the end.
+    ///
+    /// Table entries only cut off when their score falls outside the
+    /// window, so nodes inside it always search and keep a full PV.
+    #[allow(clippy::too_many_arguments)]
+    fn negamax(
+        &mut self,
+        board: &OthelloBoard,
+        key: u64,
+        depth: u32,
+        mut alpha: i32,
+        beta: i32,
+        pv: &mut Vec<Move>,
+        pv_hint: &[Move],
+    ) -> i32 {
+        self.nodes += 1;
+        self.clock_countdown = self.clock_countdown.saturating_sub(1);
+        pv.clear();
+
+        let moves = board.legal_moves();
+        if moves == 0 {
+            let mut next = *board;
+            if !next.pass() {
+                return final_score(board);
+            }
+            let mut child_pv = Vec::new();
+            let hint = match pv_hint.split_first() {
+                Some((Move::Pass, rest)) => rest,
+                _ => &[],
+            };
+            let score = -self.negamax(
+                &next,
+                hash::update_key_pass(key),
+                depth,
+                -beta,
+                -alpha,
+                &mut child_pv,
+                hint,
+            );
+            pv.push(Move::Pass);
+            pv.extend(child_pv);
+            return score;
+        }
+        if depth == 0 {
+            return self.evaluator.evaluate(board);
+        }
+        if self.check_limits() {
+            return 0;
+        }
+
+        let entry = self.table.probe(key);
+        if let Some(entry) = entry
+            && entry.depth >= depth
+        {
+            let cutoff = match entry.bound {
+                Bound::Exact => entry.score <= alpha || entry.score >= beta,
+                Bound::Lower => entry.score >= beta,
+                Bound::Upper => entry.score <= alpha,
+            };
+            if cutoff {
+                return entry.score;
+            }
+        }
+
+        let hint_move = match pv_hint.first() {
+            Some(Move::Place(square)) => Some(*square),
+            _ => match entry.and_then(|entry| entry.best_move) {
+                Some(Move::Place(square)) => Some(square),
+                _ => None,
+            },
+        };
+        let original_alpha = alpha;
+        let mut child_pv = Vec::new();
+        let mut best = -SCORE_INF;
+        let mut best_move = None;
+        for square in order_moves(moves, hint_move) {
+            let mut next = *board;
+            let Ok(outcome) = next.try_place(square) else {
+                continue;
+            };
+            let hint = match pv_hint.split_first() {
+                Some((&Move::Place(first), rest)) if first == square => rest,
+                _ => &[],
+            };
+            let score = -self.negamax(
+                &next,
+                hash::update_key(key, board.turn, &outcome),
+                depth - 1,
+                -beta,
+                -alpha,
+                &mut child_pv,
+                hint,
+            );
+            if self.stopped {
+                return 0;
+            }
+            if score > best {
+                best = score;
+                best_move = Some(Move::Place(square));
+                if score > alpha {
+                    alpha = score;
+                    pv.clear();
+                    pv.push(Move::Place(square));
+                    pv.extend_from_slice(&child_pv);
+                    if alpha >= beta {
+                        break;
+                    }
+                }
+            }
+        }
+
+        let bound = if best <= original_alpha {
+            Bound::Upper
+        } else if best >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.store(
+            key,
+            TtEntry {
+                depth,
+                bound,
+                score: best,
+                best_move,
+            },
+        );
+        best
+    }
+}
+
+/// Orders moves by positional weight, trying `first` before anything else.
+pub(crate) fn order_moves(moves: u64, first: Option<Square>) -> Vec<Square> {
+    let mut squares: Vec<Square> = core::Bitboard(moves).into_iter().collect();
+    squares.sort_by_key(|square| {
+        let index = square.index() as usize;
+        (
+            first != Some(*square),
+            std::cmp::Reverse(eval::SQUARE_WEIGHTS[index]),
+        )
+    });
+    squares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        SCORE_WIN,
+        test_util::{endgame, play_pv, solve},
+    };
+    use core::OthelloColor;
+
+    #[test]
+    fn test_search_returns_legal_move() {
+        let board = OthelloBoard::new();
+        let result = search(&board, &SearchLimits::depth(4));
+        assert_eq!(result.depth, 4);
+        let Some(Move::Place(square)) = result.best_move else {
+            panic!("expected a placement");
+        };
+        assert!(board.can_place_square(square));
+        assert_eq!(result.pv.len(), 4);
+        play_pv(&board, &result.pv);
+        assert!(result.nodes > 0);
+    }
+
+    #[test]
+    fn test_order_moves() {
+        let moves = Square::A1.mask() | Square::B2.mask() | Square::C4.mask();
+        assert_eq!(
+            order_moves(moves, None),
+            [Square::A1, Square::C4, Square::B2]
+        );
+        assert_eq!(
+            order_moves(moves, Some(Square::B2)),
+            [Square::B2, Square::A1, Square::C4]
+        );
+    }
+
+    #[test]
+    fn test_search_matches_minimax_in_endgame() {
+        for seed in 1..=3 {
+            let board = endgame(seed, 9);
+            let result = search(&board, &SearchLimits::default());
+            assert_eq!(result.score, solve(&board));
+            play_pv(&board, &result.pv);
+        }
+    }
+
+    #[test]
+    fn test_search_passes_when_forced() {
+        let board = OthelloBoard::from_bitboards(
+            Square::B1.mask() | Square::C1.mask(),
+            Square::A1.mask(),
+            OthelloColor::Black,
+        );
+        let result = search(&board, &SearchLimits::depth(3));
+        assert_eq!(result.best_move, Some(Move::Pass));
+        // White then wipes out Black.
+        assert_eq!(result.score, -(SCORE_WIN + 4));
+    }
+
+    #[test]
+    fn test_search_finished_game() {
+        let board = OthelloBoard::from_bitboards(1, 1 << 63, OthelloColor::Black);
+        let result = search(&board, &SearchLimits::depth(5));
+        assert_eq!(result.best_move, None);
+        assert_eq!(result.score, 0);
+        assert!(result.pv.is_empty());
+    }
+
+    #[test]
+    fn test_node_limit() {
+        let board = OthelloBoard::new();
+        let result = search(&board, &SearchLimits::nodes(500));
+        assert!(result.best_move.is_some());
+        assert!(result.depth >= 1);
+        assert!(result.nodes < 2000);
+    }
+
+    #[test]
+    fn test_time_limit() {
+        let board = OthelloBoard::new();
+        let start = Instant::now();
+        let result = search(&board, &SearchLimits::time(Duration::from_millis(50)));
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert!(result.best_move.is_some());
+    }
+
+    #[test]
+    fn test_search_with_custom_evaluator() {
+        // Greedy disc count against the default evaluator from the same
+        // position; both must return legal moves, and the closure is used.
+        let board = OthelloBoard::from_transcript("f5d6c3d3c4f4").unwrap();
+        let calls = std::cell::Cell::new(0);
+        let greedy = |board: &OthelloBoard| {
+            calls.set(calls.get() + 1);
+            let (player, opponent) = board.get_current_player_and_opponent();
+            player.count_ones() as i32 - opponent.count_ones() as i32
+        };
+        let result = search_with(&board, &SearchLimits::depth(3), &greedy);
+        assert!(calls.get() > 0);
+        play_pv(&board, &result.pv);
+
+        let baseline = search_with(&board, &SearchLimits::depth(3), &eval::SquareTableEvaluator);
+        play_pv(&board, &baseline.pv);
+    }
+
+    #[test]
+    fn test_reused_table() {
+        let board = OthelloBoard::from_transcript("f5d6c3d3c4f4").unwrap();
+        let evaluator = PatternEvaluator::default();
+        let table = TranspositionTable::new(1 << 20);
+        let first = search_with_table(&board, &SearchLimits::depth(6), &evaluator, &table);
+        let second = search_with_table(&board, &SearchLimits::depth(6), &evaluator, &table);
+        assert_eq!(first.score, second.score);
+        assert!(second.nodes < first.nodes);
+        play_pv(&board, &second.pv);
+
+        // The table changes how much is searched, not the result.
+        let tiny = TranspositionTable::new(0);
+        let fresh = search_with_table(&board, &SearchLimits::depth(6), &evaluator, &tiny);
+        assert_eq!(fresh.score, first.score);
+    }
+
+    #[test]
+    fn test_parallel_search() {
+        let evaluator = PatternEvaluator::default();
+        let board = OthelloBoard::from_transcript("f5d6c3d3c4f4").unwrap();
+        let single = search_parallel(
+            &board,
+            &SearchLimits::depth(6),
+            &evaluator,
+            &TranspositionTable::new(1 << 20),
+            1,
+        );
+        let reference = search_with_table(
+            &board,
+            &SearchLimits::depth(6),
+            &evaluator,
+            &TranspositionTable::new(1 << 20),
+        );
+        assert_eq!(single, reference);
+
+        let parallel = search_parallel(
+            &board,
+            &SearchLimits::depth(6),
+            &evaluator,
+            &TranspositionTable::new(1 << 20),
+            4,
+        );
+        assert_eq!(parallel.depth, 6);
+        play_pv(&board, &parallel.pv);
+
+        for seed in 1..=2 {
+            let board = endgame(seed, 9);
+            let table = TranspositionTable::new(1 << 20);
+            let result = search_parallel(&board, &SearchLimits::default(), &evaluator, &table, 3);
+            assert_eq!(result.score, solve(&board));
+        }
+    }
+
+    #[test]
+    fn test_search_is_deterministic() {
+        let board = OthelloBoard::from_transcript("f5d6c3d3c4f4").unwrap();
+        let a = search(&board, &SearchLimits::depth(5));
+        let b = search(&board, &SearchLimits::depth(5));
+        assert_eq!(a, b);
+    }
+}
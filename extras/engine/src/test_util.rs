@@ -0,0 +1,55 @@
+use core::{Move, OthelloBoard, testing::random_games};
+
+use crate::final_score;
+
+/// Plain minimax to the end of the game, scoring finished positions with
+/// `leaf`.
+pub fn minimax(board: &OthelloBoard, leaf: fn(&OthelloBoard) -> i32) -> i32 {
+    let moves = board.legal_moves();
+    if moves == 0 {
+        let mut next = *board;
+        if !next.pass() {
+            return leaf(board);
+        }
+        return -minimax(&next, leaf);
+    }
+    core::Bitboard(moves)
+        .into_iter()
+        .map(|square| {
+            let mut next = *board;
+            next.try_place(square).unwrap();
+            -minimax(&next, leaf)
+        })
+        .max()
+        .unwrap()
+}
+
+pub fn solve(board: &OthelloBoard) -> i32 {
+    minimax(board, final_score)
+}
+
+pub fn disc_diff(board: &OthelloBoard) -> i32 {
+    let (player, opponent) = board.get_current_player_and_opponent();
+    player.count_ones() as i32 - opponent.count_ones() as i32
+}
+
+/// Plays pseudo-random moves from the initial position until only
+/// `empties` squares are left (or the game ends).
+pub fn endgame(seed: u64, empties: u32) -> OthelloBoard {
+    let game = random_games(seed).next().unwrap();
+    let mut positions = game.positions.into_iter();
+    let last = positions.next_back().unwrap();
+    positions
+        .find(|board| 64 - (board.black | board.white).count_ones() <= empties)
+        .unwrap_or(last)
+}
+
+/// Plays `pv` from `board`, panicking on an illegal move, and returns the
+/// resulting position.
+pub fn play_pv(board: &OthelloBoard, pv: &[Move]) -> OthelloBoard {
+    let mut board = *board;
+    for &mv in pv {
+        board.play(mv).unwrap();
+    }
+    board
+}
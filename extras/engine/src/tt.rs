@@ -0,0 +1,288 @@
+//! Shared transposition table.
+//!
+//! Every slot is a pair of atomics holding `key ^ data` and `data`, so a
+//! reader detects an entry torn by a concurrent writer as a miss instead of
+//! taking a lock. Each bucket has a depth-preferred slot, which only yields
+//! to deeper or newer entries, and an always-replace slot.
+
+use core::{Move, Square};
+use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Bound {
+    /// The score is exact.
+    Exact,
+    /// The search failed high: the score is a lower bound.
+    Lower,
+    /// The search failed low: the score is an upper bound.
+    Upper,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TtEntry {
+    /// Remaining search depth, saturated at 255.
+    pub depth: u32,
+    pub bound: Bound,
+    pub score: i32,
+    pub best_move: Option<Move>,
+}
+
+impl TtEntry {
+    fn pack(&self, generation: u8) -> u64 {
+        let bound = match self.bound {
+            Bound::Exact => 1,
+            Bound::Lower => 2,
+            Bound::Upper => 3,
+        };
+        let best_move = match self.best_move {
+            None => 0,
+            Some(Move::Pass) => 1,
+            Some(Move::Place(square)) => square.index() as u64 + 2,
+        };
+        (self.score as u32 as u64)
+            | (self.depth.min(u8::MAX as u32) as u64) << 32
+            | bound << 40
+            | best_move << 42
+            | (generation as u64) << 50
+    }
+
+    fn unpack(data: u64) -> Option<Self> {
+        let bound = match (data >> 40) & 0b11 {
+            1 => Bound::Exact,
+            2 => Bound::Lower,
+            3 => Bound::Upper,
+            _ => return None,
+        };
+        let best_move = match (data >> 42) as u8 {
+            0 => None,
+            1 => Some(Move::Pass),
+            index => Some(Move::Place(Square::from_index(index - 2)?)),
+        };
+        Some(TtEntry {
+            depth: ((data >> 32) & 0xff) as u32,
+            bound,
+            score: data as u32 as i32,
+            best_move,
+        })
+    }
+}
+
+#[inline]
+fn depth_of(data: u64) -> u32 {
+    ((data >> 32) & 0xff) as u32
+}
+
+#[inline]
+fn generation_of(data: u64) -> u8 {
+    (data >> 50) as u8
+}
+
+#[derive(Default)]
+struct Slot {
+    key: AtomicU64,
+    data: AtomicU64,
+}
+
+impl Slot {
+    #[inline]
+    fn load(&self) -> (u64, u64) {
+        let data = self.data.load(Ordering::Relaxed);
+        (self.key.load(Ordering::Relaxed) ^ data, data)
+    }
+
+    #[inline]
+    fn store(&self, key: u64, data: u64) {
+        self.key.store(key ^ data, Ordering::Relaxed);
+        self.data.store(data, Ordering::Relaxed);
+    }
+
+    #[inline]
+    fn is_empty(data: u64) -> bool {
+        (data >> 40) & 0b11 == 0
+    }
+}
+
+#[derive(Default)]
+struct Bucket {
+    depth_preferred: Slot,
+    always: Slot,
+}
+
+pub struct TranspositionTable {
+    buckets: Box<[Bucket]>,
+    generation: AtomicU8,
+}
+
+impl TranspositionTable {
+    /// Creates a table using at most `bytes` of memory, rounded down to a
+    /// power of two buckets (at least one).
+    pub fn new(bytes: usize) -> Self {
+        let count = (bytes / size_of::<Bucket>()).max(1);
+        let count = 1 << count.ilog2();
+        TranspositionTable {
+            buckets: (0..count).map(|_| Bucket::default()).collect(),
+            generation: AtomicU8::new(0),
+        }
+    }
+
+    /// Number of entries the table can hold.
+    pub fn capacity(&self) -> usize {
+        self.buckets.len() * 2
+    }
+
+    /// Memory used by the entries in bytes.
+    pub fn size_in_bytes(&self) -> usize {
+        self.buckets.len() * size_of::<Bucket>()
+    }
+
+    /// Marks entries stored so far as old, letting new searches replace
+    /// them even in depth-preferred slots.
+    pub fn new_search(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn clear(&self) {
+        for bucket in self.buckets.iter() {
+            bucket.depth_preferred.store(0, 0);
+            bucket.always.store(0, 0);
+        }
+    }
+
+    #[inline]
+    fn bucket(&self, key: u64) -> &Bucket {
+        &self.buckets[key as usize & (self.buckets.len() - 1)]
+    }
+
+    pub fn probe(&self, key: u64) -> Option<TtEntry> {
+        let bucket = self.bucket(key);
+        [&bucket.depth_preferred, &bucket.always]
+            .into_iter()
+            .find_map(|slot| match slot.load() {
+                (stored, data) if stored == key => TtEntry::unpack(data),
+                _ => None,
+            })
+    }
+
+    /// Stores `entry` in the depth-preferred slot if it holds the same
+    /// position, nothing, an entry from an earlier search or a shallower
+    /// one; otherwise in the always-replace slot.
+    pub fn store(&self, key: u64, entry: TtEntry) {
+        let generation = self.generation.load(Ordering::Relaxed) & 0x3f;
+        let data = entry.pack(generation);
+        let bucket = self.bucket(key);
+        let (stored, current) = bucket.depth_preferred.load();
+        if stored == key
+            || Slot::is_empty(current)
+            || generation_of(current) != generation
+            || entry.depth >= depth_of(current)
+        {
+            bucket.depth_preferred.store(key, data);
+        } else {
+            bucket.always.store(key, data);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn entry(depth: u32, score: i32) -> TtEntry {
+        TtEntry {
+            depth,
+            bound: Bound::Exact,
+            score,
+            best_move: Some(Move::Place(Square::D3)),
+        }
+    }
+
+    #[test]
+    fn test_pack_round_trip() {
+        for best_move in [None, Some(Move::Pass), Some(Move::Place(Square::H8))] {
+            for bound in [Bound::Exact, Bound::Lower, Bound::Upper] {
+                for score in [0, -1, i32::MIN, i32::MAX, 100_042] {
+                    let entry = TtEntry {
+                        depth: 17,
+                        bound,
+                        score,
+                        best_move,
+                    };
+                    assert_eq!(TtEntry::unpack(entry.pack(63)), Some(entry));
+                }
+            }
+        }
+        assert_eq!(TtEntry::unpack(0), None);
+    }
+
+    #[test]
+    fn test_memory_budget() {
+        let table = TranspositionTable::new(1 << 20);
+        assert_eq!(table.size_in_bytes(), 1 << 20);
+        assert_eq!(table.capacity(), (1 << 20) / 16);
+        let table = TranspositionTable::new(1000);
+        assert!(table.size_in_bytes() <= 1000);
+        assert_eq!(TranspositionTable::new(0).capacity(), 2);
+    }
+
+    #[test]
+    fn test_probe_and_store() {
+        let table = TranspositionTable::new(1 << 12);
+        assert_eq!(table.probe(42), None);
+        table.store(42, entry(3, 7));
+        assert_eq!(table.probe(42), Some(entry(3, 7)));
+        // Same position, shallower: still replaced.
+        table.store(42, entry(1, 9));
+        assert_eq!(table.probe(42), Some(entry(1, 9)));
+        table.clear();
+        assert_eq!(table.probe(42), None);
+    }
+
+    #[test]
+    fn test_replacement() {
+        let table = TranspositionTable::new(1 << 12);
+        let stride = table.buckets.len() as u64;
+        let (deep, shallow, other) = (1, 1 + stride, 1 + 2 * stride);
+
+        table.store(deep, entry(8, 1));
+        // A shallower entry for another position goes to the always slot...
+        table.store(shallow, entry(2, 2));
+        assert_eq!(table.probe(deep), Some(entry(8, 1)));
+        assert_eq!(table.probe(shallow), Some(entry(2, 2)));
+        // ...and is overwritten by the next one.
+        table.store(other, entry(3, 3));
+        assert_eq!(table.probe(shallow), None);
+        assert_eq!(table.probe(deep), Some(entry(8, 1)));
+
+        // Entries from an earlier search give way.
+        table.new_search();
+        table.store(shallow, entry(2, 2));
+        assert_eq!(table.probe(deep), None);
+        assert_eq!(table.probe(shallow), Some(entry(2, 2)));
+    }
+
+    #[test]
+    fn test_concurrent_access() {
+        let table = Arc::new(TranspositionTable::new(1 << 10));
+        let threads: Vec<_> = (0..4u64)
+            .map(|thread| {
+                let table = Arc::clone(&table);
+                std::thread::spawn(move || {
+                    for i in 0..10_000u64 {
+                        let key = i.wrapping_mul(0x9e37_79b9_7f4a_7c15) ^ thread;
+                        let score = (key % 1000) as i32;
+                        table.store(key, entry(i as u32 % 10, score));
+                        // Whatever is found must be an entry stored for
+                        // that key, never a torn mix.
+                        if let Some(found) = table.probe(key) {
+                            assert_eq!(found.score, score);
+                        }
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+    }
+}